@@ -0,0 +1,70 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use std::path;
+use std::time::Duration;
+use sub_auto_rename::{MovieFile, RenameOptions, SubtitleFile};
+
+/// Matches every movie file to its subtitle file in a directory of 1000 files
+fn matching_benchmark(c: &mut Criterion) {
+    let movie_files: Vec<MovieFile> = (1..=500)
+        .map(|episode| {
+            let path = format!(
+                "Some.Show.S{:02}E{:02}.720p.WEB-GROUP.mkv",
+                episode / 50,
+                episode % 50
+            );
+            MovieFile::new(path::PathBuf::from(path), None).unwrap()
+        })
+        .collect();
+
+    let subtitle_files: Vec<SubtitleFile> = (1..=500)
+        .rev()
+        .map(|episode| {
+            let path = format!(
+                "some show s{:02} e{:02} subtitles.srt",
+                episode / 50,
+                episode % 50
+            );
+            SubtitleFile::try_from(path::PathBuf::from(path)).unwrap()
+        })
+        .collect();
+
+    let options = RenameOptions::default();
+
+    let mut group = c.benchmark_group("matching");
+    group.sample_size(10);
+    group.measurement_time(Duration::from_secs(10));
+    group.bench_function("1000 files", |b| {
+        b.iter(|| {
+            movie_files
+                .iter()
+                .filter(|movie_file| {
+                    subtitle_files.iter().any(|subtitle_file| {
+                        subtitle_file.planned_path(movie_file, &options).is_some()
+                    })
+                })
+                .count()
+        })
+    });
+    group.finish();
+}
+
+/// Reads the signatures of 1000 movie file names
+fn signature_benchmark(c: &mut Criterion) {
+    let movie_files: Vec<MovieFile> = (1..=1000)
+        .map(|episode| {
+            let path = format!(
+                "Some.Show.S{:02}E{:02}.720p.WEB-GROUP.mkv",
+                episode / 50,
+                episode % 50
+            );
+            MovieFile::new(path::PathBuf::from(path), None).unwrap()
+        })
+        .collect();
+
+    c.bench_function("signatures of 1000 names", |b| {
+        b.iter(|| movie_files.iter().filter_map(MovieFile::signature).count())
+    });
+}
+
+criterion_group!(benches, matching_benchmark, signature_benchmark);
+criterion_main!(benches);
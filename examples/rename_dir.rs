@@ -0,0 +1,63 @@
+//! Renames the subtitle files of a directory after the movie files they match, using the
+//! library alone.
+//!
+//! ```text
+//! cargo run --example rename_dir -- path/to/videos
+//! ```
+
+use std::{env, fs, path};
+use sub_auto_rename::{MovieFile, RenameOptions, RenameOutcome, SubtitleFile};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let directory = env::args_os()
+        .nth(1)
+        .map(path::PathBuf::from)
+        .ok_or("usage: rename_dir <directory>")?;
+
+    // Sorting the paths so that the pairs don't depend on the order of the directory entries
+    let mut paths = fs::read_dir(&directory)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    paths.sort_unstable();
+
+    let mut movie_files = Vec::new();
+    let mut subtitle_files = Vec::new();
+    for path in paths.into_iter().filter(|path| path.is_file()) {
+        if let Some(movie_file) = MovieFile::new(path.clone(), None) {
+            movie_files.push(movie_file);
+        } else if let Ok(subtitle_file) = SubtitleFile::try_from(path) {
+            subtitle_files.push(subtitle_file);
+        }
+    }
+
+    let options = RenameOptions::default();
+    let mut movie_file_paired = vec![false; movie_files.len()];
+    for subtitle_file in &subtitle_files {
+        // Taking the first movie file the subtitle file matches, each movie file getting a
+        // single subtitle file
+        let Some(movie_index) = (0..movie_files.len()).find(|movie_index| {
+            !movie_file_paired[*movie_index]
+                && subtitle_file
+                    .planned_path(&movie_files[*movie_index], &options)
+                    .is_some()
+        }) else {
+            println!("No movie file for '{}'", subtitle_file);
+            continue;
+        };
+        movie_file_paired[movie_index] = true;
+
+        match subtitle_file
+            .rename_using_movie_file_with_options(&movie_files[movie_index], &options)
+        {
+            Ok(RenameOutcome::Moved(new_path)) => {
+                println!("Renamed '{}' to '{}'", subtitle_file, new_path.display())
+            }
+            Ok(RenameOutcome::AlreadyCorrect) => {
+                println!("'{}' is already named after its movie file", subtitle_file)
+            }
+            Err(err) => println!("Renaming '{}' failed: {}", subtitle_file, err),
+        }
+    }
+
+    Ok(())
+}
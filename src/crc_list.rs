@@ -0,0 +1,100 @@
+use anyhow::{bail, Result};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, BufRead, Read};
+use std::path;
+
+/// How many bytes of a movie file are read at once when computing its CRC32
+const CRC_BUFFER_SIZE: usize = 64 * 1024;
+
+/// Reads a hash list made of "hash,episode" lines, like "ABCD1234,27", mapping the CRC32 hashes
+/// of movie files, written in hexadecimal, to their episode numbers.
+/// Empty lines and lines starting with '#' are ignored
+///
+/// # Errors
+/// Returns an error when a line is not in the "hash,episode" format, when a hash is listed
+/// more than once, or when reading fails
+pub fn read_crc_list(reader: impl BufRead) -> Result<HashMap<u32, u32>> {
+    let mut crc_list = HashMap::new();
+
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let entry = line.split_once(',').and_then(|(hash, episode)| {
+            let hash = hash.trim().trim_start_matches('[').trim_end_matches(']');
+            if hash.len() != 8 {
+                return None;
+            }
+            Some((
+                u32::from_str_radix(hash, 16).ok()?,
+                episode.trim().parse::<u32>().ok()?,
+            ))
+        });
+
+        let Some((hash, episode)) = entry else {
+            bail!(
+                "Line {} of the hash list is not in the 'hash,episode' format, like 'ABCD1234,27'",
+                index + 1
+            );
+        };
+
+        if crc_list.insert(hash, episode).is_some() {
+            bail!(
+                "Line {} of the hash list lists the hash {:08X} once again",
+                index + 1,
+                hash
+            );
+        }
+    }
+
+    Ok(crc_list)
+}
+
+/// Returns the CRC32 of the content of the file
+pub fn file_crc32(file_path: &path::Path) -> io::Result<u32> {
+    let mut file = fs::File::open(file_path)?;
+    let mut hasher = crc32fast::Hasher::new();
+    let mut buffer = vec![0; CRC_BUFFER_SIZE];
+
+    loop {
+        match file.read(&mut buffer)? {
+            0 => return Ok(hasher.finalize()),
+            read_len => hasher.update(&buffer[..read_len]),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{file_crc32, read_crc_list};
+    use crate::test_directory::test_directory;
+    use std::fs;
+
+    #[test]
+    fn read_crc_list_test() {
+        let crc_list = "# hash,episode\nABCD1234,27\n[0000ff01], 28\n\n".as_bytes();
+        let crc_list = read_crc_list(crc_list).unwrap();
+
+        assert_eq!(crc_list.len(), 2);
+        assert_eq!(crc_list.get(&0xABCD1234), Some(&27));
+        assert_eq!(crc_list.get(&0xFF01), Some(&28));
+
+        assert!(read_crc_list("ABCD123,27\n".as_bytes()).is_err());
+        assert!(read_crc_list("ABCD1234,S02E05\n".as_bytes()).is_err());
+        assert!(read_crc_list("ABCD1234,27\nabcd1234,28\n".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn file_crc32_test() {
+        let directory = test_directory("crc-file");
+        let file_path = directory.join("episode.mkv");
+        fs::write(&file_path, "123456789").unwrap();
+
+        // The check value of CRC32
+        assert_eq!(file_crc32(&file_path).unwrap(), 0xCBF43926);
+    }
+}
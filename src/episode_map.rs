@@ -0,0 +1,65 @@
+use anyhow::{bail, Result};
+use std::collections::HashMap;
+use std::io::BufRead;
+use sub_auto_rename::Signature;
+
+/// Reads an episode map made of "absolute,signature" lines, like "27,S02E05", mapping the
+/// absolute episode numbers to the signatures of the episodes they stand for.
+/// Empty lines and lines starting with '#' are ignored
+///
+/// # Errors
+/// Returns an error when a line is not in the "absolute,signature" format, when an absolute
+/// number is mapped more than once, or when reading fails
+pub fn read_episode_map(reader: impl BufRead) -> Result<HashMap<u32, Signature>> {
+    let mut episode_map = HashMap::new();
+
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let mapping = line.split_once(',').and_then(|(absolute, signature)| {
+            Some((
+                absolute.trim().parse::<u32>().ok()?,
+                Signature::parse(signature.trim())?,
+            ))
+        });
+
+        let Some((absolute, signature)) = mapping else {
+            bail!(
+                "Line {} of the episode map is not in the 'absolute,signature' format, like '27,S02E05'",
+                index + 1
+            );
+        };
+
+        if episode_map.insert(absolute, signature).is_some() {
+            bail!(
+                "Line {} of the episode map maps the episode {} once again",
+                index + 1,
+                absolute
+            );
+        }
+    }
+
+    Ok(episode_map)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::read_episode_map;
+    use sub_auto_rename::Signature;
+
+    #[test]
+    fn read_episode_map_test() {
+        let episode_map = "# absolute,signature\n26,S02E04\n27, S02E05\n\n".as_bytes();
+        let episode_map = read_episode_map(episode_map).unwrap();
+
+        assert_eq!(episode_map.len(), 2);
+        assert_eq!(episode_map.get(&27), Signature::parse("S02E05").as_ref());
+
+        assert!(read_episode_map("27,S02\n".as_bytes()).is_err());
+        assert!(read_episode_map("27,S02E05\n27,S02E06\n".as_bytes()).is_err());
+    }
+}
@@ -7,18 +7,78 @@
 //! when they are used, that is when a movie file has been matched and when a subtitle
 //! file has been renamed. This helps prevent unecessary reuse of these struct.
 
-use anyhow::Result;
-use name_signature::{episode_name_signature_check, MatchSignature};
+use name_signature::{
+    add_default_season, compact_signature, episode_name_signature_check, get_absolute_episode,
+    get_match_key, get_signature, get_signature_end, has_episode_signature, titles_differ,
+    SignatureOptions,
+};
+use std::collections::HashMap;
 use std::ffi::OsStr;
 use std::fs;
 use std::path;
 use thiserror::Error;
 
 mod name_signature;
+mod srt;
+mod target_name;
+#[cfg(feature = "tar")]
+mod tarred;
+#[cfg(test)]
+mod test_directory;
+#[cfg(feature = "zip")]
+mod zipped;
+
+pub use name_signature::{
+    inspect_signature, match_names, same_episode_number, show_title, MatchKey, MatchSignature,
+    Signature, SignatureError, SignatureInspection, SignaturePart,
+};
+#[cfg(feature = "tar")]
+pub use tarred::rename_tar_entries;
+#[cfg(feature = "zip")]
+pub use zipped::ZippedSubtitleFile;
 
 const SUBTITLE_FILE_EXTENSION: &str = "srt";
+const BACKUP_FILE_EXTENSION: &str = "bak";
+/// How much of the start of a file is read to find out whether it has SRT content
+const SRT_SNIFF_LENGTH: u64 = 1024;
+#[cfg(feature = "gzip")]
+const GZIP_FILE_EXTENSION: &str = "gz";
+/// The extension of the file a compressed subtitle file is decompressed into before taking its
+/// place
+#[cfg(feature = "gzip")]
+const PARTIAL_SUBTITLE_FILE_EXTENSION: &str = "srt.part";
+const RETRY_BACKOFF: std::time::Duration = std::time::Duration::from_millis(50);
 const MOVIE_FILE_EXTENSIONS: &[&str] = &["mp4", "mkv", "flv", "avi", "3gp", "mov"];
 
+/// Returns the movie file extensions recognized by default, that is without any extra extensions
+pub fn default_movie_extensions() -> &'static [&'static str] {
+    MOVIE_FILE_EXTENSIONS
+}
+
+/// Returns the subtitle file extensions recognized by default
+pub fn default_subtitle_extensions() -> &'static [&'static str] {
+    &[SUBTITLE_FILE_EXTENSION]
+}
+
+/// The version of the crate
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Returns the version of the crate
+pub fn version() -> &'static str {
+    VERSION
+}
+
+/// Returns the movie file extensions supported without providing any extra extensions,
+/// same as [`default_movie_extensions`]
+pub fn supported_movie_extensions() -> &'static [&'static str] {
+    default_movie_extensions()
+}
+
+/// Returns the subtitle file extensions supported, same as [`default_subtitle_extensions`]
+pub fn supported_subtitle_extensions() -> &'static [&'static str] {
+    default_subtitle_extensions()
+}
+
 /// Error that can be returned when performing operations related to a subtitle file
 #[derive(Debug, Error)]
 pub enum SubtitleFileError {
@@ -35,63 +95,835 @@ pub enum SubtitleFileError {
     /// This error is returned when a subtitle file has already been renamed thus not bothering
     /// with issuing an unecessary rename system call.
     /// One of the obvious scenario when this can happen is when a user reruns the program more than once
-    /// in the same directory
+    /// in the same directory. Renaming using a movie file reports it as
+    /// [`RenameOutcome::AlreadyCorrect`] instead
     #[error("The subtitle file has already been renamed")]
     AlreadyRenamed,
 
+    /// This error is returned when the file the subtitle file is supposed to be renamed to
+    /// already exists, thus not overwriting it
+    #[error("The target file already exists: {}", .0.display())]
+    TargetExists(path::PathBuf),
+
     /// This error is returned when a error is return by fs::rename() function
-    #[error("There is an error related to the filesystem: (0)")]
+    #[error("There is an error related to the filesystem: {0}")]
     FileSystem(String),
 }
 
+/// What renaming a subtitle file using a movie file did
+#[derive(Debug, PartialEq)]
+pub enum RenameOutcome {
+    /// The subtitle file has been moved to this path
+    Moved(path::PathBuf),
+
+    /// The subtitle file was already named after the movie file, nothing has been done
+    AlreadyCorrect,
+}
+
 /// Struct representing a subtitle file
 #[derive(Debug)]
 pub struct SubtitleFile {
     subtitle_file_path: path::PathBuf,
+    /// Whether the file is a gzip compressed subtitle file, like "name.srt.gz"
+    compressed: bool,
+}
+
+/// Options changing how a subtitle file gets renamed
+#[derive(Debug, Default, Clone)]
+pub struct RenameOptions {
+    /// Whether to lowercase the new subtitle file name
+    pub lowercase_output: bool,
+
+    /// Whether to keep a backup copy of the subtitle file before renaming it
+    pub keep_original: bool,
+
+    /// Extensions, like "srt" and "sub", of the copies of the subtitle file made next to the
+    /// movie file by [`SubtitleFile::copy_using_movie_file_with_options`]
+    pub copy_as: Vec<String>,
+
+    /// Whether to rename the subtitle file even when its signature doesn't match
+    /// the signature of the movie file
+    pub force: bool,
+
+    /// Whether to strip a trailing release group tag, like "-RARBG" or "[EtHD]", from the
+    /// new subtitle file name
+    pub strip_release_group: bool,
+
+    /// Whether to match the subtitle file with movie files having a clearly different title,
+    /// like "Better Call Saul S01E01.srt" with "Breaking.Bad.S01E01.mkv", which are told
+    /// apart by default
+    pub ignore_titles: bool,
+
+    /// Whether titles differing in case only, like "breaking.bad" and "Breaking.Bad", are
+    /// told apart when comparing titles, the case is ignored by default
+    pub case_sensitive_titles: bool,
+
+    /// Whether to drop everything after the signature in the new subtitle file name, like the
+    /// episode title of "Show.S01E02.The.Title", giving "Show.S01E02.srt"
+    pub trim_after_signature: bool,
+
+    /// Whether to remove the separators between the season and the episode of the signature in
+    /// the new subtitle file name, like "Show.S01E02.srt" for the "Show.S01 E02.mkv" movie file
+    pub normalize_signature: bool,
+
+    /// Whether to replace the dots separating the words of the new subtitle file name with
+    /// spaces, like "Breaking Bad S01E02.srt" instead of "Breaking.Bad.S01E02.srt"
+    pub dots_to_spaces: bool,
+
+    /// Text put before the stem of the new subtitle file name, like "[SUB] " giving
+    /// "[SUB] Show.S01E02.srt"
+    pub prefix: String,
+
+    /// Text put after the stem of the new subtitle file name, before the modifiers and the
+    /// extension, like ".tagged" giving "Show.S01E02.tagged.srt"
+    pub suffix: String,
+
+    /// How many times to retry renaming the subtitle file when it fails with a transient
+    /// filesystem error, like a busy file on a network mount
+    pub retries: u32,
+
+    /// Extensions of sidecar files, like "nfo" or "chapters.xml", that share the stem of the
+    /// subtitle file and get renamed alongside it
+    pub sidecar_extensions: Vec<String>,
+
+    /// Absolute episode numbers, numbering the episodes continuously across seasons, mapped to
+    /// the signatures of the episodes they stand for. Names only having an episode number are
+    /// matched using this mapping
+    pub episode_map: HashMap<u32, Signature>,
+
+    /// Whether to move an existing target file to the trash of the system and rename anyway,
+    /// instead of failing with [`SubtitleFileError::TargetExists`]. Requires the "trash" feature
+    pub trash_existing_target: bool,
+
+    /// Whether to read seasons written in Roman numerals, like "Season IV" or "S.IV", when
+    /// matching. This is opt-in as words of titles can be mistaken for Roman numerals
+    pub roman_numeral_seasons: bool,
+
+    /// Whether to read volumes, like "Vol.1", as seasons when matching
+    pub volume_seasons: bool,
+
+    /// Extra markers of seasons, like "t" for the Spanish "temporada" in "T01C02", read when
+    /// names have no built-in season marker
+    pub season_markers: Vec<String>,
+
+    /// Extra markers of episodes, like "c" for the Spanish "capítulo" in "T01C02", read when
+    /// names have no built-in episode marker
+    pub episode_markers: Vec<String>,
+
+    /// What to do when the path the subtitle file gets renamed to already exists
+    pub on_conflict: ConflictPolicy,
+
+    /// The player whose subtitle loading rules the new subtitle file name has to follow. The
+    /// name options changing the stem, like [`RenameOptions::lowercase_output`], are ignored
+    /// for players only loading subtitle files named after the exact stem of the movie file
+    pub player: Option<Player>,
+
+    /// The media server whose naming conventions the new subtitle file name follows, giving
+    /// names like "Show Name - s01e02.srt" whatever the name of the movie file
+    pub target: Option<Target>,
+
+    /// A number added to the episode of the subtitle file when matching it with movie files,
+    /// for subtitle packs numbering the episodes off by one
+    pub episode_offset: i32,
+
+    /// Whether to read four digits codes like "0102" as signatures, like S01E02, when file
+    /// names have no other signature. This is opt-in as years like "1999" look the same
+    pub numeric_code: bool,
+
+    /// Whether to read "#" numbers, like the "#102" of "Show #102", as absolute episode
+    /// numbers, matching names without signatures having the same number
+    pub hash_episodes: bool,
+
+    /// The season of the file names only having an episode, like "Show.E02" for miniseries.
+    /// The season is written in the new subtitle file name, like "Show.S01E02.srt"
+    pub default_season: Option<u32>,
+
+    /// A regex with "season" and "episode" named groups, like
+    /// `Vol(?P<season>\d+)-Part(?P<episode>\d+)`, reading the signatures of the file names
+    /// instead of the built-in scanner. Requires the "pattern" feature
+    #[cfg(feature = "pattern")]
+    pub pattern: Option<regex::Regex>,
+}
+
+/// What to do when the path a subtitle file gets renamed to already exists
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum ConflictPolicy {
+    /// Leave the existing file alone and fail with [`SubtitleFileError::TargetExists`]
+    #[default]
+    Skip,
+    /// Replace the existing file
+    Overwrite,
+    /// Rename the subtitle file to a free path instead, like "name (1).srt"
+    Rename,
+}
+
+/// Players whose rules for loading external subtitle files the new subtitle file names follow
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum Player {
+    /// mpv, which loads "movie.srt" and "movie.en.srt" like names, the stem of the subtitle
+    /// file being exactly the stem of the movie file followed by at most one suffix
+    Mpv,
+}
+
+/// Media servers whose naming conventions the new subtitle file names can follow
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum Target {
+    /// Plex, which recommends "Show Name (2019) - s01e02 - Episode Title" like names
+    Plex,
+}
+
+impl RenameOptions {
+    /// Returns the options changing how signatures are read from names
+    fn signature_options(&self) -> SignatureOptions<'_> {
+        SignatureOptions {
+            episode_map: Some(&self.episode_map),
+            roman_numeral_seasons: self.roman_numeral_seasons,
+            volume_seasons: self.volume_seasons,
+            season_markers: &self.season_markers,
+            episode_markers: &self.episode_markers,
+            episode_offset: self.episode_offset,
+            numeric_code: self.numeric_code,
+            hash_episodes: self.hash_episodes,
+            default_season: self.default_season,
+            #[cfg(feature = "pattern")]
+            pattern: self.pattern.as_ref(),
+        }
+    }
 }
 
 impl SubtitleFile {
-    /// Renames the subtitle file using the name of a movie file
+    /// Renames the subtitle file using the name of a movie file, returning whether it has been
+    /// moved or was already named after the movie file
     ///
     /// # Errors
     /// This function return errors when the rename operation fails due to permission, etc, or
     /// when the subtitle file name and the movie file name have no matching season and episode
     /// signatures, that is the word S01EO5 that imply that the files are of the First season
     /// at episode Five
-    pub fn rename_using_movie_file(&self, movie_file: &MovieFile) -> Result<(), SubtitleFileError> {
-        if let MatchSignature::Match = episode_name_signature_check(
-            movie_file.get_path().as_os_str(),
+    pub fn rename_using_movie_file(
+        &self,
+        movie_file: &MovieFile,
+    ) -> Result<RenameOutcome, SubtitleFileError> {
+        self.rename_using_movie_file_with_options(movie_file, &RenameOptions::default())
+    }
+
+    /// Renames the subtitle file using the name of a movie file, applying the given rename options
+    ///
+    /// # Errors
+    /// Same as [`SubtitleFile::rename_using_movie_file`]
+    pub fn rename_using_movie_file_with_options(
+        &self,
+        movie_file: &MovieFile,
+        options: &RenameOptions,
+    ) -> Result<RenameOutcome, SubtitleFileError> {
+        match self.rename_after_movie_file(movie_file, options) {
+            Ok(new_path) => Ok(RenameOutcome::Moved(new_path)),
+            Err(SubtitleFileError::AlreadyRenamed) => Ok(RenameOutcome::AlreadyCorrect),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Same as [`SubtitleFile::rename_using_movie_file_with_options`], returning the path the
+    /// subtitle file has been renamed to, and the [`SubtitleFileError::AlreadyRenamed`] error
+    /// when it was already named after the movie file
+    fn rename_after_movie_file(
+        &self,
+        movie_file: &MovieFile,
+        options: &RenameOptions,
+    ) -> Result<path::PathBuf, SubtitleFileError> {
+        let new_subtitle_file_name = match self.planned_path(movie_file, options) {
+            Some(new_subtitle_file_name) => new_subtitle_file_name,
+            None => return Err(SubtitleFileError::MovieSubFileNamesMismatch),
+        };
+
+        // Comparing the whole file names, as subtitle files having SRT content under another
+        // extension still need to be renamed to get the subtitle file extension
+        if new_subtitle_file_name.file_name() == self.subtitle_file_path.file_name() {
+            return Err(SubtitleFileError::AlreadyRenamed);
+        }
+
+        self.rename_to_path_with_options(&new_subtitle_file_name, options)
+    }
+
+    /// Copies the subtitle file next to the movie file, taking the name of the movie file, once
+    /// for each extension of [`RenameOptions::copy_as`], returning the paths of the copies.
+    /// The subtitle file itself is left alone, as is a copy that would be the subtitle file
+    ///
+    /// # Errors
+    /// This function returns errors when the subtitle file name and the movie file name have
+    /// no matching season and episode signatures, when a copy already exists unless the
+    /// conflict policy is to overwrite, or when copying fails
+    pub fn copy_using_movie_file_with_options(
+        &self,
+        movie_file: &MovieFile,
+        options: &RenameOptions,
+    ) -> Result<Vec<path::PathBuf>, SubtitleFileError> {
+        let new_subtitle_file_path = self
+            .planned_path(movie_file, options)
+            .ok_or(SubtitleFileError::MovieSubFileNamesMismatch)?;
+
+        let mut copy_paths = Vec::new();
+        for extension in &options.copy_as {
+            let copy_path =
+                new_subtitle_file_path.with_extension(extension.trim_start_matches('.'));
+            if copy_path == self.subtitle_file_path {
+                continue;
+            }
+            if copy_path.exists() && options.on_conflict != ConflictPolicy::Overwrite {
+                return Err(SubtitleFileError::TargetExists(copy_path));
+            }
+
+            if let Err(err) = retry_transient(options.retries, || self.copy_to(&copy_path)) {
+                return Err(SubtitleFileError::FileSystem(err.to_string()));
+            }
+            copy_paths.push(copy_path);
+        }
+
+        Ok(copy_paths)
+    }
+
+    /// Returns the path of the subtitle file
+    pub fn path(&self) -> &path::Path {
+        &self.subtitle_file_path
+    }
+
+    /// Renames the subtitle file to the given path without checking any signatures, returning
+    /// the path the subtitle file has been renamed to.
+    /// The subtitle file extension is added when the given path has no extension. Nothing is
+    /// done when the given path is the current path of the subtitle file.
+    ///
+    /// # Errors
+    /// This function returns an error when the target path already exists and is a different
+    /// file than the subtitle file, unless the conflict policy of the options used says
+    /// otherwise, or when the rename operation fails due to permission, etc
+    pub fn rename_to_path(&self, target: &path::Path) -> Result<path::PathBuf, SubtitleFileError> {
+        self.rename_to_path_with_options(target, &RenameOptions::default())
+    }
+
+    /// Same as [`SubtitleFile::rename_to_path`] but applying the backup and retry rename options,
+    /// the name related options only apply when renaming using a movie file
+    ///
+    /// # Errors
+    /// Same as [`SubtitleFile::rename_to_path`], plus when the backup copy can't be made
+    pub fn rename_to_path_with_options(
+        &self,
+        target: &path::Path,
+        options: &RenameOptions,
+    ) -> Result<path::PathBuf, SubtitleFileError> {
+        let mut target = target.to_path_buf();
+        if target.extension().is_none() {
+            let mut target_file_name = target.file_name().unwrap_or_default().to_os_string();
+            target_file_name.push(".");
+            target_file_name.push(SUBTITLE_FILE_EXTENSION);
+            target.set_file_name(target_file_name);
+        }
+
+        // Renaming a file to itself is a no-op on some platforms and an error on others.
+        // Only identical paths are checked, as paths differing in case only may point to the
+        // same file on case insensitive filesystems while still needing the rename
+        if target == self.subtitle_file_path {
+            return Ok(target);
+        }
+
+        if target.exists() && !is_same_file(&self.subtitle_file_path, &target) {
+            if options.trash_existing_target {
+                if let Err(err) = trash_file(&target) {
+                    return Err(SubtitleFileError::FileSystem(err));
+                }
+            } else {
+                match options.on_conflict {
+                    ConflictPolicy::Skip => return Err(SubtitleFileError::TargetExists(target)),
+                    // Renaming replaces the existing target
+                    ConflictPolicy::Overwrite => {}
+                    ConflictPolicy::Rename => target = numbered_free_path(&target),
+                }
+            }
+        }
+
+        // Compressed subtitle files are kept as they are, which makes a backup of them
+        if options.keep_original && !self.compressed {
+            if let Err(err) = self.backup() {
+                return Err(SubtitleFileError::FileSystem(err.to_string()));
+            }
+        }
+
+        if let Err(err) = self.move_to(&target, options) {
+            return Err(SubtitleFileError::FileSystem(err.to_string()));
+        }
+
+        self.rename_sidecars(&target, &options.sidecar_extensions);
+        Ok(target)
+    }
+
+    /// Moves the subtitle file to the target. Compressed subtitle files are decompressed into
+    /// the target instead, the compressed file only being removed then, unless the original
+    /// is kept
+    fn move_to(&self, target: &path::Path, options: &RenameOptions) -> std::io::Result<()> {
+        #[cfg(feature = "gzip")]
+        if self.compressed {
+            decompress_gzip(&self.subtitle_file_path, target)?;
+            if !options.keep_original {
+                // The subtitle file has been renamed at this point, whatever happens to the
+                // compressed file
+                if let Err(err) = fs::remove_file(&self.subtitle_file_path) {
+                    log::warn!(
+                        "Failed removing compressed subtitle file '{}': {}",
+                        self.subtitle_file_path.display(),
+                        err
+                    );
+                }
+            }
+            return Ok(());
+        }
+
+        retry_transient(options.retries, || {
+            fs::rename(&self.subtitle_file_path, target)
+        })
+    }
+
+    /// Copies the subtitle file to the target, decompressing compressed subtitle files
+    fn copy_to(&self, target: &path::Path) -> std::io::Result<()> {
+        #[cfg(feature = "gzip")]
+        if self.compressed {
+            return decompress_gzip(&self.subtitle_file_path, target);
+        }
+
+        fs::copy(&self.subtitle_file_path, target).map(|_| ())
+    }
+
+    /// Moves the subtitle file back from the path it has been renamed to. Compressed subtitle
+    /// files are compressed back when the compressed file is gone
+    fn undo_rename(&self, new_path: &path::Path) -> std::io::Result<()> {
+        #[cfg(feature = "gzip")]
+        if self.compressed {
+            if !self.subtitle_file_path.exists() {
+                compress_gzip(new_path, &self.subtitle_file_path)?;
+            }
+            return fs::remove_file(new_path);
+        }
+
+        fs::rename(new_path, &self.subtitle_file_path)
+    }
+
+    /// Returns the stem of the subtitle file, the one of the decompressed subtitle file for
+    /// compressed subtitle files, like "name" for "name.srt.gz"
+    fn file_stem(&self) -> &OsStr {
+        let file_stem = self.subtitle_file_path.file_stem().unwrap_or_default();
+        if self.compressed {
+            path::Path::new(file_stem).file_stem().unwrap_or(file_stem)
+        } else {
+            file_stem
+        }
+    }
+
+    /// Renames the sidecar files with the given extensions that share the stem of the subtitle
+    /// file, giving them the stem of the target. Failing to rename a sidecar file doesn't undo
+    /// the subtitle file rename, the failure is logged instead
+    fn rename_sidecars(&self, target: &path::Path, extensions: &[String]) {
+        let stem = self.file_stem();
+        let target_stem = target.file_stem().unwrap_or_default();
+
+        for extension in extensions {
+            let extension = extension.trim_start_matches('.');
+
+            let mut sidecar_file_name = stem.to_os_string();
+            sidecar_file_name.push(".");
+            sidecar_file_name.push(extension);
+            let sidecar_path = self.subtitle_file_path.with_file_name(sidecar_file_name);
+            if !sidecar_path.exists() {
+                continue;
+            }
+
+            let mut new_sidecar_file_name = target_stem.to_os_string();
+            new_sidecar_file_name.push(".");
+            new_sidecar_file_name.push(extension);
+            let new_sidecar_path = target.with_file_name(new_sidecar_file_name);
+            if new_sidecar_path.exists() {
+                log::warn!(
+                    "Not renaming sidecar file '{}' as '{}' already exists",
+                    sidecar_path.display(),
+                    new_sidecar_path.display()
+                );
+                continue;
+            }
+
+            if let Err(err) = fs::rename(&sidecar_path, &new_sidecar_path) {
+                log::warn!(
+                    "Failed renaming sidecar file '{}': {}",
+                    sidecar_path.display(),
+                    err
+                );
+            }
+        }
+    }
+
+    /// Returns the season and episode signature used to match the subtitle file with movie
+    /// files, if it has one
+    pub fn signature(&self) -> Option<Signature> {
+        get_signature(self.matching_path(&SignatureOptions::default()).as_os_str())
+    }
+
+    /// Returns what the subtitle file is matched with movie files on when renaming with the
+    /// given options, see [`MatchKey`]. A subtitle file can only be renamed using a movie file
+    /// having the same key, the episode offset of the options being applied to the subtitle file
+    pub fn match_key(&self, options: &RenameOptions) -> Option<MatchKey> {
+        let signature_options = options.signature_options();
+        get_match_key(
+            self.matching_path(&signature_options).as_os_str(),
+            &signature_options,
+            true,
+        )
+    }
+
+    /// Returns the episode number of the subtitle file when its name has no season, as is the
+    /// case for names numbering the episodes continuously across seasons, like "Show - 27.srt"
+    pub fn absolute_episode(&self) -> Option<u32> {
+        get_absolute_episode(
             self.subtitle_file_path.as_os_str(),
+            &SignatureOptions::default(),
+        )
+    }
+
+    /// Returns whether the subtitle file and the movie file have matching season and episode
+    /// signatures, that is whether renaming the subtitle file using the movie file would go
+    /// ahead, without touching the filesystem
+    pub fn matches(&self, movie_file: &MovieFile) -> bool {
+        self.planned_path(movie_file, &RenameOptions::default())
+            .is_some()
+    }
+
+    /// Returns the stem of the movie file, like "Show.S01E02" for "Show.S01E02.mkv", when the
+    /// subtitle file matches it, see [`SubtitleFile::matches`], that is the name the subtitle
+    /// file would be given before its extension. Returns None when they don't match
+    pub fn matched_movie_name(&self, movie_file: &MovieFile) -> Option<String> {
+        self.planned_path(movie_file, &RenameOptions::default())?;
+
+        Some(
+            movie_file
+                .path()
+                .file_stem()?
+                .to_string_lossy()
+                .into_owned(),
+        )
+    }
+
+    /// Returns the path the subtitle file would be renamed to using the name of a movie file,
+    /// without touching the filesystem.
+    /// Returns None when the subtitle file name and the movie file name have no matching
+    /// season and episode signatures, or clearly different titles, unless the rename is forced
+    pub fn planned_path(
+        &self,
+        movie_file: &MovieFile,
+        options: &RenameOptions,
+    ) -> Option<path::PathBuf> {
+        if options.force {
+            return Some(self.new_subtitle_file_path(movie_file, options));
+        }
+
+        let matching_path = self.matching_path(&options.signature_options());
+        match episode_name_signature_check(
+            movie_file.path().as_os_str(),
+            matching_path.as_os_str(),
+            &options.signature_options(),
         ) {
-            let mut new_subtitle_file_name = path::PathBuf::from(movie_file.get_path());
-            new_subtitle_file_name.set_extension(SUBTITLE_FILE_EXTENSION);
-
-            if movie_file.get_path().file_stem().unwrap_or(OsStr::new(""))
-                == self
-                    .subtitle_file_path
-                    .file_stem()
-                    .unwrap_or(OsStr::new(""))
+            // Shows dumped in the same directory share signatures, their titles tell them apart
+            MatchSignature::Match
+                if !options.ignore_titles
+                    && titles_differ(
+                        movie_file.path().as_os_str(),
+                        matching_path.as_os_str(),
+                        options.case_sensitive_titles,
+                    ) =>
             {
-                return Err(SubtitleFileError::AlreadyRenamed);
+                log::debug!(
+                    "Not matching '{}' with '{}' as their titles differ",
+                    self,
+                    movie_file
+                );
+                None
             }
+            MatchSignature::Match => Some(self.new_subtitle_file_path(movie_file, options)),
+            MatchSignature::NoMatch => None,
+        }
+    }
 
-            if let Err(err) = fs::rename(&self.subtitle_file_path, new_subtitle_file_name) {
-                return Err(SubtitleFileError::FileSystem(err.to_string()));
+    /// Returns the path used to match the subtitle file with movie files, that is its own path,
+    /// or the path of its parent directory when the file name has no signature. The latter is
+    /// the case for releases having a "Subs/<movie-stem>/English.srt" per movie folder layout
+    fn matching_path(&self, signature_options: &SignatureOptions) -> &path::Path {
+        let subtitle_file_path = self.subtitle_file_path.as_os_str();
+        let reads_absolute_episodes = signature_options.hash_episodes
+            || signature_options
+                .episode_map
+                .is_some_and(|episode_map| !episode_map.is_empty());
+        let has_absolute_episode = reads_absolute_episodes
+            && get_absolute_episode(subtitle_file_path, signature_options).is_some();
+
+        if has_episode_signature(subtitle_file_path, signature_options) || has_absolute_episode {
+            return &self.subtitle_file_path;
+        }
+
+        self.subtitle_file_path
+            .parent()
+            .unwrap_or(&self.subtitle_file_path)
+    }
+
+    /// Constructs a SubtitleFile from a gzip compressed subtitle file, like "name.srt.gz",
+    /// without touching it. The subtitle file is only decompressed when it gets renamed or
+    /// copied, straight into its new path, the compressed file being removed once renamed
+    /// unless the original is kept
+    ///
+    /// # Errors
+    /// This function returns an error when the file name isn't like "name.srt.gz", or when the
+    /// decompressed subtitle file, like "name.srt", already exists beside it
+    #[cfg(feature = "gzip")]
+    pub fn from_gzip(compressed_file_path: path::PathBuf) -> Result<Self, SubtitleFileError> {
+        if compressed_file_path.extension() != Some(OsStr::new(GZIP_FILE_EXTENSION)) {
+            return Err(SubtitleFileError::InvalidSubtileFileName);
+        }
+        let subtitle_file = Self::try_from(compressed_file_path.with_extension(""))?;
+
+        // The subtitle file has been decompressed already, it is the one to rename
+        if subtitle_file.subtitle_file_path.exists() {
+            return Err(SubtitleFileError::TargetExists(
+                subtitle_file.subtitle_file_path,
+            ));
+        }
+
+        Ok(Self {
+            subtitle_file_path: compressed_file_path,
+            compressed: true,
+        })
+    }
+
+    /// Constructs a SubtitleFile from a file having SRT content regardless of its extension,
+    /// like SRT subtitles released with a ".txt" extension. The subtitle file extension is
+    /// given to the file when it gets renamed using a movie file. UTF-8, UTF-16 and Latin-1
+    /// contents are recognized
+    ///
+    /// # Errors
+    /// This function returns an error when the content of the file doesn't look like SRT, or
+    /// when the file can't be read
+    pub fn from_srt_content(path: path::PathBuf) -> Result<Self, SubtitleFileError> {
+        let mut start_of_content = Vec::new();
+        let read_result = fs::File::open(&path).and_then(|file| {
+            std::io::Read::read_to_end(
+                &mut std::io::Read::take(file, SRT_SNIFF_LENGTH),
+                &mut start_of_content,
+            )
+        });
+        if let Err(err) = read_result {
+            return Err(SubtitleFileError::FileSystem(err.to_string()));
+        }
+
+        if srt::looks_like_srt(&srt::decode(&start_of_content)) {
+            Ok(Self {
+                subtitle_file_path: path,
+                compressed: false,
+            })
+        } else {
+            Err(SubtitleFileError::InvalidSubtileFileName)
+        }
+    }
+
+    /// Copies the subtitle file to a backup file named like "name.srt.bak", appending a
+    /// numeric suffix when such a backup already exists so that it doesn't get overwritten
+    fn backup(&self) -> std::io::Result<path::PathBuf> {
+        let mut backup_file_name = self.subtitle_file_path.as_os_str().to_os_string();
+        backup_file_name.push(".");
+        backup_file_name.push(BACKUP_FILE_EXTENSION);
+
+        let mut backup_file_path = path::PathBuf::from(&backup_file_name);
+        let mut suffix = 1;
+        while backup_file_path.exists() {
+            let mut suffixed_backup_file_name = backup_file_name.clone();
+            suffixed_backup_file_name.push(format!(".{}", suffix));
+            backup_file_path = path::PathBuf::from(suffixed_backup_file_name);
+            suffix += 1;
+        }
+
+        fs::copy(&self.subtitle_file_path, &backup_file_path)?;
+        Ok(backup_file_path)
+    }
+
+    /// Builds the path the subtitle file will have after being renamed after the movie file
+    fn new_subtitle_file_path(
+        &self,
+        movie_file: &MovieFile,
+        options: &RenameOptions,
+    ) -> path::PathBuf {
+        let movie_file_stem = movie_file.path().file_stem().unwrap_or_default();
+        let mut new_file_name = match movie_file_stem.to_str() {
+            // mpv only loads subtitle files named after the exact stem of the movie file
+            Some(movie_file_stem) if options.player != Some(Player::Mpv) => {
+                transform_file_stem(movie_file_stem, options).into()
             }
-            return Ok(());
+            // Transforming names that aren't valid UTF-8 would replace their invalid bytes,
+            // corrupting them, so they are kept as they are
+            _ => movie_file_stem.to_os_string(),
+        };
+        if options.player != Some(Player::Mpv) {
+            let mut tagged_file_name = std::ffi::OsString::from(&options.prefix);
+            tagged_file_name.push(new_file_name);
+            tagged_file_name.push(&options.suffix);
+            new_file_name = tagged_file_name;
         }
-        Err(SubtitleFileError::MovieSubFileNamesMismatch)
+
+        // Modifiers like the "forced" of "name.forced.srt" tell what the subtitle file is, they
+        // are carried over to the new name
+        // Duplicate downloads like "name.forced (1).srt" would hide the modifiers
+        let subtitle_file_stem = self.file_stem().to_string_lossy();
+        let subtitle_file_stem = target_name::strip_duplicate_marker(&subtitle_file_stem);
+        let modifiers = target_name::subtitle_modifiers(subtitle_file_stem).to_string();
+        if options.player == Some(Player::Mpv) {
+            // mpv only reads a single suffix, which also carries the language
+            new_file_name.push(target_name::mpv_suffix(subtitle_file_stem));
+        } else if options.lowercase_output {
+            new_file_name.push(modifiers.to_lowercase());
+        } else {
+            new_file_name.push(modifiers);
+        }
+
+        new_file_name.push(".");
+        new_file_name.push(SUBTITLE_FILE_EXTENSION);
+
+        movie_file.path().with_file_name(new_file_name)
     }
 }
 
-impl TryFrom<path::PathBuf> for SubtitleFile {
-    type Error = SubtitleFileError;
+/// Applies the name related rename options to the stem of the movie file, giving the stem of
+/// the new subtitle file name
+fn transform_file_stem(file_stem: &str, options: &RenameOptions) -> String {
+    let target_file_stem = match options.target {
+        Some(Target::Plex) => target_name::plex_stem(file_stem),
+        None => None,
+    };
+    let mut file_stem = target_file_stem.as_deref().unwrap_or(file_stem);
+
+    if options.trim_after_signature {
+        if let Some(signature_end) = get_signature_end(file_stem) {
+            file_stem = &file_stem[..signature_end];
+        }
+    }
+
+    if options.strip_release_group {
+        file_stem = target_name::strip_release_group(file_stem);
+    }
 
-    fn try_from(value: path::PathBuf) -> std::result::Result<Self, Self::Error> {
+    let mut file_stem = file_stem.to_string();
+
+    if options.normalize_signature {
+        if let Some(compact_file_stem) = compact_signature(&file_stem) {
+            file_stem = compact_file_stem;
+        }
+    }
+
+    if let Some(default_season) = options.default_season {
+        if let Some(seasoned_file_stem) = add_default_season(&file_stem, default_season) {
+            file_stem = seasoned_file_stem;
+        }
+    }
+
+    if options.dots_to_spaces {
+        file_stem = target_name::dots_to_spaces(&file_stem);
+    }
+
+    if options.lowercase_output {
+        file_stem = file_stem.to_lowercase();
+    }
+
+    file_stem
+}
+
+/// Error returned by [`rename_all_or_rollback`] when renaming one of the subtitle files fails
+#[derive(Debug, Error)]
+#[error("Renaming '{}' failed: {source}", .subtitle_file_path.display())]
+pub struct BatchError {
+    /// The path of the subtitle file whose rename failed
+    pub subtitle_file_path: path::PathBuf,
+
+    /// Why renaming the subtitle file failed
+    #[source]
+    pub source: SubtitleFileError,
+
+    /// The paths of the subtitle files that couldn't be renamed back during the rollback
+    pub rollback_failures: Vec<path::PathBuf>,
+}
+
+/// Renames the subtitle file of every pair using its movie file, in order. When a rename fails
+/// the renames already done are reversed, making the batch all or nothing on a best-effort
+/// basis: backups and sidecar files are not rolled back, and the subtitle files that can't be
+/// renamed back are listed in the error. Subtitle files already renamed are left alone
+///
+/// # Errors
+/// Returns an error on the first subtitle file failing to be renamed, see
+/// [`SubtitleFile::rename_using_movie_file`]
+pub fn rename_all_or_rollback(
+    pairs: &[(&SubtitleFile, &MovieFile)],
+    options: &RenameOptions,
+) -> Result<(), BatchError> {
+    let mut renamed_paths = Vec::new();
+
+    for (subtitle_file, movie_file) in pairs {
+        match subtitle_file.rename_after_movie_file(movie_file, options) {
+            Ok(new_path) => renamed_paths.push((subtitle_file, new_path)),
+            Err(SubtitleFileError::AlreadyRenamed) => {}
+            Err(err) => {
+                let rollback_failures = renamed_paths
+                    .into_iter()
+                    .rev()
+                    .filter_map(|(renamed_subtitle_file, new_path)| {
+                        renamed_subtitle_file
+                            .undo_rename(&new_path)
+                            .err()
+                            .map(|err| {
+                                log::error!(
+                                    "Failed renaming '{}' back to '{}': {}",
+                                    new_path.display(),
+                                    renamed_subtitle_file,
+                                    err
+                                );
+                                new_path
+                            })
+                    })
+                    .collect();
+
+                return Err(BatchError {
+                    subtitle_file_path: subtitle_file.path().to_path_buf(),
+                    source: err,
+                    rollback_failures,
+                });
+            }
+        }
+    }
+
+    Ok(())
+}
+
+impl SubtitleFile {
+    /// Same as [`SubtitleFile::try_from`], which ignores the case of the extension, comparing
+    /// the extension exactly when `ignore_case` is false
+    ///
+    /// # Errors
+    /// This function returns an error when the file name does not end with the subtitle file
+    /// extension, or has nothing before it, like ".srt"
+    pub fn try_from_with_extension_case(
+        value: path::PathBuf,
+        ignore_case: bool,
+    ) -> Result<Self, SubtitleFileError> {
+        if !has_file_stem(&value) {
+            return Err(SubtitleFileError::InvalidSubtileFileName);
+        }
         if let Some(extension) = value.extension() {
-            if extension == SUBTITLE_FILE_EXTENSION {
+            if extension == SUBTITLE_FILE_EXTENSION
+                || (ignore_case && extension.eq_ignore_ascii_case(SUBTITLE_FILE_EXTENSION))
+            {
                 return Ok(Self {
                     subtitle_file_path: value,
+                    compressed: false,
                 });
             }
         }
@@ -99,6 +931,14 @@ impl TryFrom<path::PathBuf> for SubtitleFile {
     }
 }
 
+impl TryFrom<path::PathBuf> for SubtitleFile {
+    type Error = SubtitleFileError;
+
+    fn try_from(value: path::PathBuf) -> Result<Self, Self::Error> {
+        Self::try_from_with_extension_case(value, true)
+    }
+}
+
 impl std::fmt::Display for SubtitleFile {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let path_to_display = self.subtitle_file_path.to_string_lossy();
@@ -106,6 +946,128 @@ impl std::fmt::Display for SubtitleFile {
     }
 }
 
+/// Returns whether the file name has something before its extension other than dots. Names
+/// like ".srt" or "..srt" have no stem to match or to rename after
+fn has_file_stem(file_path: &path::Path) -> bool {
+    file_path
+        .file_stem()
+        .is_some_and(|file_stem| !file_stem.to_string_lossy().trim_matches('.').is_empty())
+}
+
+/// Decompresses the gzip compressed file into the target, through a partial file next to it so
+/// that an existing target is only replaced once the decompression succeeded
+#[cfg(feature = "gzip")]
+fn decompress_gzip(compressed_file_path: &path::Path, target: &path::Path) -> std::io::Result<()> {
+    let partial_path = target.with_extension(PARTIAL_SUBTITLE_FILE_EXTENSION);
+    let decompress = || -> std::io::Result<()> {
+        let mut decoder = flate2::read::GzDecoder::new(fs::File::open(compressed_file_path)?);
+        let mut subtitle = fs::File::create(&partial_path)?;
+        std::io::copy(&mut decoder, &mut subtitle)?;
+        fs::rename(&partial_path, target)
+    };
+
+    decompress().inspect_err(|_| {
+        // Not leaving a partially decompressed subtitle file behind
+        let _ = fs::remove_file(&partial_path);
+    })
+}
+
+/// Compresses the file into the gzip compressed file, undoing [`decompress_gzip`]
+#[cfg(feature = "gzip")]
+fn compress_gzip(file_path: &path::Path, compressed_file_path: &path::Path) -> std::io::Result<()> {
+    let mut encoder = flate2::write::GzEncoder::new(
+        fs::File::create(compressed_file_path)?,
+        flate2::Compression::default(),
+    );
+    std::io::copy(&mut fs::File::open(file_path)?, &mut encoder)?;
+    encoder.finish()?;
+    Ok(())
+}
+
+/// Moves the file to the trash of the system so that it can still be recovered
+#[cfg(feature = "trash")]
+fn trash_file(file_path: &path::Path) -> Result<(), String> {
+    trash::delete(file_path).map_err(|err| err.to_string())
+}
+
+/// Moves the file to the trash of the system, which fails as the support for it is not built in
+#[cfg(not(feature = "trash"))]
+fn trash_file(file_path: &path::Path) -> Result<(), String> {
+    Err(format!(
+        "Can't move '{}' to the trash, the trash feature is not enabled",
+        file_path.display()
+    ))
+}
+
+/// Returns the first path that doesn't exist among the given path with a " (1)", " (2)", etc
+/// suffix added to its stem
+fn numbered_free_path(file_path: &path::Path) -> path::PathBuf {
+    let stem = file_path.file_stem().unwrap_or_default();
+
+    (1..)
+        .map(|number| {
+            let mut file_name = stem.to_os_string();
+            file_name.push(format!(" ({})", number));
+            if let Some(extension) = file_path.extension() {
+                file_name.push(".");
+                file_name.push(extension);
+            }
+            file_path.with_file_name(file_name)
+        })
+        .find(|numbered_path| !numbered_path.exists())
+        // SAFETY: the range is endless, there is always a path that doesn't exist
+        .unwrap()
+}
+
+/// Returns whether the error is likely to go away when retrying the operation that caused it,
+/// like when a file on a network mount is busy
+fn is_transient_error(err: &std::io::Error) -> bool {
+    // EBUSY, which has the same value on Linux and macOS
+    #[cfg(unix)]
+    const RESOURCE_BUSY: i32 = 16;
+
+    #[cfg(unix)]
+    if err.raw_os_error() == Some(RESOURCE_BUSY) {
+        return true;
+    }
+
+    matches!(
+        err.kind(),
+        std::io::ErrorKind::WouldBlock
+            | std::io::ErrorKind::Interrupted
+            | std::io::ErrorKind::TimedOut
+    )
+}
+
+/// Runs the operation, retrying it up to the given number of times with an exponential backoff
+/// when it fails with a transient error. Other errors are returned immediately
+fn retry_transient(
+    retries: u32,
+    mut operation: impl FnMut() -> std::io::Result<()>,
+) -> std::io::Result<()> {
+    let mut attempt = 0;
+    loop {
+        match operation() {
+            Err(err) if attempt < retries && is_transient_error(&err) => {
+                let backoff = RETRY_BACKOFF * 2_u32.saturating_pow(attempt);
+                log::warn!("{}, retrying in {:?}", err, backoff);
+                std::thread::sleep(backoff);
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Returns whether both paths point to the same file, like on case insensitive filesystems
+/// where the paths only differ in case
+fn is_same_file(first_path: &path::Path, second_path: &path::Path) -> bool {
+    match (fs::canonicalize(first_path), fs::canonicalize(second_path)) {
+        (Ok(first_path), Ok(second_path)) => first_path == second_path,
+        _ => false,
+    }
+}
+
 /// Struct representing a movie file
 #[derive(Debug)]
 pub struct MovieFile(path::PathBuf);
@@ -116,28 +1078,55 @@ impl MovieFile {
     /// This method takes an optional vec of extensions to include when constructing
     /// the MoviesFile, otherwise when the argument is None it will default to the
     /// built in extension.
-    /// Returns None when the path provided is of unknown extension
+    /// Returns None when the path provided is of unknown extension, or has nothing before its
+    /// extension, like ".mkv".
+    /// Extensions are compared ignoring case, so "MKV" is a movie file extension
     pub fn new(value: path::PathBuf, extra_extensions: Option<&Vec<String>>) -> Option<Self> {
+        Self::new_with_extension_case(value, extra_extensions, true)
+    }
+
+    /// Same as [`MovieFile::new`], comparing the extensions exactly when `ignore_case` is false
+    pub fn new_with_extension_case(
+        value: path::PathBuf,
+        extra_extensions: Option<&Vec<String>>,
+        ignore_case: bool,
+    ) -> Option<Self> {
+        if !has_file_stem(&value) {
+            return None;
+        }
         if let Some(extension) = value.extension() {
+            let extension = extension.to_string_lossy();
+            let is_extension = |val: &str| {
+                val == extension || (ignore_case && val.eq_ignore_ascii_case(&extension))
+            };
+
             // Checking the extra extensions first
             if let Some(extra_extensions) = extra_extensions {
-                if extra_extensions
-                    .iter()
-                    .any(|val| *val == extension.to_string_lossy())
-                {
+                if extra_extensions.iter().any(|val| is_extension(val)) {
                     return Some(Self(value));
                 }
             }
             // Checking the default extensions when no extra extensions are provided
-            if MOVIE_FILE_EXTENSIONS.iter().any(|val| *val == extension) {
+            if MOVIE_FILE_EXTENSIONS.iter().any(|val| is_extension(val)) {
                 return Some(Self(value));
             }
         }
         None
     }
 
+    /// Returns the season and episode signature of the MovieFile, if its name has one
+    pub fn signature(&self) -> Option<Signature> {
+        get_signature(self.0.as_os_str())
+    }
+
+    /// Returns what the MovieFile is matched with subtitle files on when renaming with the
+    /// given options, see [`SubtitleFile::match_key`]
+    pub fn match_key(&self, options: &RenameOptions) -> Option<MatchKey> {
+        get_match_key(self.0.as_os_str(), &options.signature_options(), false)
+    }
+
     /// Returns the path of the MovieFile
-    fn get_path(&self) -> &path::Path {
+    pub fn path(&self) -> &path::Path {
         &self.0
     }
 }
@@ -151,8 +1140,15 @@ impl std::fmt::Display for MovieFile {
 
 #[cfg(test)]
 mod tests {
-    use super::MovieFile;
+    use super::{
+        rename_all_or_rollback, retry_transient, ConflictPolicy, MovieFile, Player, RenameOptions,
+        RenameOutcome, Signature, SubtitleFile, SubtitleFileError, Target,
+    };
+    use crate::test_directory::test_directory;
     use crate::MOVIE_FILE_EXTENSIONS;
+    use std::collections::HashMap;
+    use std::fs;
+    use std::io;
     use std::path;
 
     #[test]
@@ -171,6 +1167,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::almost_complete_range)]
     fn movie_file_creation_with_extra_extension_test() {
         let extra_extension: Vec<String> = ('a'..'z').map(|ext| ext.to_string()).collect();
 
@@ -186,4 +1183,987 @@ mod tests {
 
         assert_eq!(total_movie_files_created, movie_paths.len())
     }
+
+    #[test]
+    fn default_extensions_test() {
+        assert!(super::default_movie_extensions().contains(&"mkv"));
+        assert!(super::default_subtitle_extensions().contains(&"srt"));
+    }
+
+    #[test]
+    fn supported_formats_test() {
+        assert!(!super::supported_movie_extensions().is_empty());
+        assert!(super::supported_movie_extensions().contains(&"mp4"));
+        assert!(!super::supported_subtitle_extensions().is_empty());
+        assert!(super::supported_subtitle_extensions().contains(&"srt"));
+        assert_eq!(super::version(), env!("CARGO_PKG_VERSION"));
+    }
+
+    #[test]
+    fn new_subtitle_file_path_lowercase_output_test() {
+        let movie_file = MovieFile::new(
+            path::PathBuf::from("SHOW.S01E02.MKV"),
+            Some(&vec!["MKV".to_string()]),
+        )
+        .unwrap();
+        let subtitle_file = SubtitleFile::try_from(path::PathBuf::from("show.s01e02.srt")).unwrap();
+
+        let lowercase_options = RenameOptions {
+            lowercase_output: true,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            subtitle_file.new_subtitle_file_path(&movie_file, &lowercase_options),
+            path::PathBuf::from("show.s01e02.srt")
+        );
+        assert_eq!(
+            subtitle_file.new_subtitle_file_path(&movie_file, &RenameOptions::default()),
+            path::PathBuf::from("SHOW.S01E02.srt")
+        );
+    }
+
+    #[test]
+    fn rename_keep_original_test() {
+        let directory = test_directory("keep-original");
+        let movie_path = directory.join("show.s01e02.mkv");
+        let subtitle_path = directory.join("show s01e02 web.srt");
+        fs::write(&movie_path, "").unwrap();
+        fs::write(&subtitle_path, "subtitle").unwrap();
+        // An already existing backup should not be overwritten
+        fs::write(directory.join("show s01e02 web.srt.bak"), "old backup").unwrap();
+
+        let movie_file = MovieFile::new(movie_path, None).unwrap();
+        let subtitle_file = SubtitleFile::try_from(subtitle_path).unwrap();
+        let options = RenameOptions {
+            keep_original: true,
+            ..Default::default()
+        };
+
+        subtitle_file
+            .rename_using_movie_file_with_options(&movie_file, &options)
+            .unwrap();
+
+        assert!(directory.join("show.s01e02.srt").exists());
+        assert_eq!(
+            fs::read_to_string(directory.join("show s01e02 web.srt.bak")).unwrap(),
+            "old backup"
+        );
+        assert_eq!(
+            fs::read_to_string(directory.join("show s01e02 web.srt.bak.1")).unwrap(),
+            "subtitle"
+        );
+    }
+
+    #[test]
+    fn rename_outcome_test() {
+        let directory = test_directory("rename-outcome");
+        let movie_path = directory.join("Show.S01E02.mkv");
+        let subtitle_path = directory.join("show s01e02 web.srt");
+        fs::write(&movie_path, "").unwrap();
+        fs::write(&subtitle_path, "").unwrap();
+
+        let movie_file = MovieFile::new(movie_path, None).unwrap();
+        assert_eq!(
+            SubtitleFile::try_from(subtitle_path)
+                .unwrap()
+                .rename_using_movie_file(&movie_file)
+                .unwrap(),
+            RenameOutcome::Moved(directory.join("Show.S01E02.srt"))
+        );
+
+        // Renaming once again has nothing left to do
+        assert_eq!(
+            SubtitleFile::try_from(directory.join("Show.S01E02.srt"))
+                .unwrap()
+                .rename_using_movie_file(&movie_file)
+                .unwrap(),
+            RenameOutcome::AlreadyCorrect
+        );
+        assert!(directory.join("Show.S01E02.srt").exists());
+    }
+
+    #[test]
+    fn rename_forced_mismatched_pair_test() {
+        let directory = test_directory("forced-pair");
+        let movie_path = directory.join("show.s03e04.mkv");
+        let subtitle_path = directory.join("show.s01e01.srt");
+        fs::write(&movie_path, "").unwrap();
+        fs::write(&subtitle_path, "").unwrap();
+
+        let movie_file = MovieFile::new(movie_path, None).unwrap();
+        let subtitle_file = SubtitleFile::try_from(subtitle_path).unwrap();
+
+        assert!(subtitle_file
+            .rename_using_movie_file_with_options(&movie_file, &RenameOptions::default())
+            .is_err());
+
+        let options = RenameOptions {
+            force: true,
+            ..Default::default()
+        };
+        subtitle_file
+            .rename_using_movie_file_with_options(&movie_file, &options)
+            .unwrap();
+
+        assert!(directory.join("show.s03e04.srt").exists());
+        assert!(!directory.join("show.s01e01.srt").exists());
+    }
+
+    #[test]
+    fn planned_path_ignores_parent_directory_test() {
+        let movie_file =
+            MovieFile::new(path::PathBuf::from("show s09e09/show.s01e02.mkv"), None).unwrap();
+        let matching_subtitle_file =
+            SubtitleFile::try_from(path::PathBuf::from("show s09e09/show s01e02.srt")).unwrap();
+        let mismatching_subtitle_file =
+            SubtitleFile::try_from(path::PathBuf::from("show s01e02/show s09e09.srt")).unwrap();
+
+        assert_eq!(
+            matching_subtitle_file.planned_path(&movie_file, &RenameOptions::default()),
+            Some(path::PathBuf::from("show s09e09/show.s01e02.srt"))
+        );
+        assert_eq!(
+            mismatching_subtitle_file.planned_path(&movie_file, &RenameOptions::default()),
+            None
+        );
+    }
+
+    #[test]
+    fn planned_path_per_movie_folder_test() {
+        let movie_file = MovieFile::new(path::PathBuf::from("dir/Show.S01E02.mkv"), None).unwrap();
+        let subtitle_file =
+            SubtitleFile::try_from(path::PathBuf::from("dir/Subs/Show.S01E02/2_English.srt"))
+                .unwrap();
+        let other_subtitle_file =
+            SubtitleFile::try_from(path::PathBuf::from("dir/Subs/Show.S01E03/2_English.srt"))
+                .unwrap();
+
+        assert_eq!(
+            subtitle_file.planned_path(&movie_file, &RenameOptions::default()),
+            Some(path::PathBuf::from("dir/Show.S01E02.srt"))
+        );
+        assert_eq!(
+            other_subtitle_file.planned_path(&movie_file, &RenameOptions::default()),
+            None
+        );
+    }
+
+    #[test]
+    fn rename_to_path_test() {
+        let directory = test_directory("rename-to-path");
+        let subtitle_path = directory.join("some subtitle.srt");
+        let existing_path = directory.join("existing.srt");
+        fs::write(&subtitle_path, "subtitle").unwrap();
+        fs::write(&existing_path, "existing").unwrap();
+
+        let subtitle_file = SubtitleFile::try_from(subtitle_path.clone()).unwrap();
+
+        assert!(matches!(
+            subtitle_file.rename_to_path(&existing_path),
+            Err(SubtitleFileError::TargetExists(_))
+        ));
+        assert_eq!(fs::read_to_string(&existing_path).unwrap(), "existing");
+
+        assert_eq!(
+            subtitle_file
+                .rename_to_path(&directory.join("target"))
+                .unwrap(),
+            directory.join("target.srt")
+        );
+        assert!(!subtitle_path.exists());
+        assert_eq!(
+            fs::read_to_string(directory.join("target.srt")).unwrap(),
+            "subtitle"
+        );
+    }
+
+    #[test]
+    fn retry_transient_test() {
+        let mut attempts = 0;
+        let result = retry_transient(3, || {
+            attempts += 1;
+            if attempts < 3 {
+                Err(io::Error::from(io::ErrorKind::WouldBlock))
+            } else {
+                Ok(())
+            }
+        });
+        assert!(result.is_ok());
+        assert_eq!(attempts, 3);
+
+        let mut attempts = 0;
+        let result = retry_transient(3, || {
+            attempts += 1;
+            Err(io::Error::from(io::ErrorKind::PermissionDenied))
+        });
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::PermissionDenied);
+        assert_eq!(attempts, 1);
+
+        let mut attempts = 0;
+        let result = retry_transient(2, || {
+            attempts += 1;
+            Err(io::Error::from(io::ErrorKind::TimedOut))
+        });
+        assert!(result.is_err());
+        assert_eq!(attempts, 3);
+    }
+
+    #[test]
+    fn planned_path_season_directory_test() {
+        let movie_file =
+            MovieFile::new(path::PathBuf::from("Show/Season 02/Show E05.mkv"), None).unwrap();
+        let subtitle_file =
+            SubtitleFile::try_from(path::PathBuf::from("Show/Season 02/E05.srt")).unwrap();
+
+        assert_eq!(
+            subtitle_file.planned_path(&movie_file, &RenameOptions::default()),
+            Some(path::PathBuf::from("Show/Season 02/Show E05.srt"))
+        );
+        assert_eq!(
+            movie_file
+                .signature()
+                .map(|signature| signature.to_string()),
+            Some("S02E05".to_string())
+        );
+    }
+
+    #[test]
+    fn rename_to_same_path_test() {
+        let directory = test_directory("rename-to-same-path");
+        let subtitle_path = directory.join("Show.S01E02.srt");
+        fs::write(&subtitle_path, "subtitle").unwrap();
+
+        let subtitle_file = SubtitleFile::try_from(subtitle_path.clone()).unwrap();
+        let options = RenameOptions {
+            keep_original: true,
+            ..Default::default()
+        };
+
+        for _ in 0..2 {
+            assert_eq!(
+                subtitle_file
+                    .rename_to_path_with_options(&subtitle_path, &options)
+                    .unwrap(),
+                subtitle_path
+            );
+        }
+        assert_eq!(fs::read_to_string(&subtitle_path).unwrap(), "subtitle");
+        // Nothing has been renamed, hence nothing has been backed up either
+        assert_eq!(fs::read_dir(&directory).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn rename_sidecars_test() {
+        let directory = test_directory("rename-sidecars");
+        let movie_path = directory.join("Show.S01E02.mkv");
+        let subtitle_path = directory.join("show s01e02 web.srt");
+        fs::write(&movie_path, "").unwrap();
+        fs::write(&subtitle_path, "subtitle").unwrap();
+        fs::write(directory.join("show s01e02 web.nfo"), "nfo").unwrap();
+        fs::write(directory.join("show s01e02 web.txt"), "txt").unwrap();
+
+        let movie_file = MovieFile::new(movie_path, None).unwrap();
+        let subtitle_file = SubtitleFile::try_from(subtitle_path).unwrap();
+        let options = RenameOptions {
+            sidecar_extensions: vec!["nfo".to_string(), "chapters.xml".to_string()],
+            ..Default::default()
+        };
+
+        subtitle_file
+            .rename_using_movie_file_with_options(&movie_file, &options)
+            .unwrap();
+
+        assert_eq!(
+            fs::read_to_string(directory.join("Show.S01E02.nfo")).unwrap(),
+            "nfo"
+        );
+        // Only the listed extensions are renamed
+        assert!(directory.join("show s01e02 web.txt").exists());
+    }
+
+    #[cfg(feature = "trash")]
+    #[test]
+    #[ignore = "moves a file to the trash of the system, run with --ignored"]
+    fn rename_trash_existing_target_test() {
+        let directory = test_directory("trash-existing-target");
+        let subtitle_path = directory.join("some subtitle.srt");
+        let existing_path = directory.join("existing.srt");
+        fs::write(&subtitle_path, "subtitle").unwrap();
+        fs::write(&existing_path, "existing").unwrap();
+
+        let subtitle_file = SubtitleFile::try_from(subtitle_path.clone()).unwrap();
+        let options = RenameOptions {
+            trash_existing_target: true,
+            ..Default::default()
+        };
+
+        subtitle_file
+            .rename_to_path_with_options(&existing_path, &options)
+            .unwrap();
+
+        assert!(!subtitle_path.exists());
+        assert_eq!(fs::read_to_string(&existing_path).unwrap(), "subtitle");
+
+        let trashed_item = trash::os_limited::list()
+            .unwrap()
+            .into_iter()
+            .find(|item| item.original_path() == existing_path)
+            .expect("the existing target should be in the trash");
+        trash::os_limited::purge_all([trashed_item]).unwrap();
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn subtitle_file_from_gzip_test() {
+        use std::io::Write;
+
+        let directory = test_directory("from-gzip");
+        let write_compressed = |file_name: &str| {
+            let compressed_path = directory.join(file_name);
+            let mut encoder = flate2::write::GzEncoder::new(
+                fs::File::create(&compressed_path).unwrap(),
+                flate2::Compression::default(),
+            );
+            encoder.write_all(b"subtitle").unwrap();
+            encoder.finish().unwrap();
+            compressed_path
+        };
+        let compressed_path = write_compressed("show s01e02.forced.srt.gz");
+        fs::write(directory.join("Show.S01E02.mkv"), "").unwrap();
+        let movie_file = MovieFile::new(directory.join("Show.S01E02.mkv"), None).unwrap();
+
+        // Nothing is written until the subtitle file gets renamed
+        let subtitle_file = SubtitleFile::from_gzip(compressed_path.clone()).unwrap();
+        assert_eq!(subtitle_file.path(), compressed_path);
+        assert!(!directory.join("show s01e02.forced.srt").exists());
+        assert_eq!(
+            subtitle_file.planned_path(&movie_file, &RenameOptions::default()),
+            Some(directory.join("Show.S01E02.forced.srt"))
+        );
+
+        assert_eq!(
+            subtitle_file.rename_using_movie_file(&movie_file).unwrap(),
+            RenameOutcome::Moved(directory.join("Show.S01E02.forced.srt"))
+        );
+        assert_eq!(
+            fs::read_to_string(directory.join("Show.S01E02.forced.srt")).unwrap(),
+            "subtitle"
+        );
+        assert!(!compressed_path.exists());
+
+        // The compressed file is the original kept
+        let compressed_path = write_compressed("show s01e02.srt.gz");
+        SubtitleFile::from_gzip(compressed_path.clone())
+            .unwrap()
+            .rename_using_movie_file_with_options(
+                &movie_file,
+                &RenameOptions {
+                    keep_original: true,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        assert!(compressed_path.exists());
+        assert!(directory.join("Show.S01E02.srt").exists());
+        assert!(!directory.join("show s01e02.srt.gz.bak").exists());
+
+        assert!(matches!(
+            SubtitleFile::from_gzip(directory.join("show s01e02.txt.gz")),
+            Err(SubtitleFileError::InvalidSubtileFileName)
+        ));
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn rename_all_or_rollback_gzip_test() {
+        use std::io::Write;
+
+        let directory = test_directory("rollback-gzip");
+        let compressed_path = directory.join("show s01e01.srt.gz");
+        let mut encoder = flate2::write::GzEncoder::new(
+            fs::File::create(&compressed_path).unwrap(),
+            flate2::Compression::default(),
+        );
+        encoder.write_all(b"subtitle").unwrap();
+        encoder.finish().unwrap();
+        fs::write(directory.join("Show.S01E01.mkv"), "").unwrap();
+        fs::write(directory.join("Show.S01E02.mkv"), "").unwrap();
+
+        let subtitle_files = [
+            SubtitleFile::from_gzip(compressed_path.clone()).unwrap(),
+            // Missing, failing the batch
+            SubtitleFile::try_from(directory.join("show s01e02.srt")).unwrap(),
+        ];
+        let movie_files = [
+            MovieFile::new(directory.join("Show.S01E01.mkv"), None).unwrap(),
+            MovieFile::new(directory.join("Show.S01E02.mkv"), None).unwrap(),
+        ];
+
+        let err = rename_all_or_rollback(
+            &[
+                (&subtitle_files[0], &movie_files[0]),
+                (&subtitle_files[1], &movie_files[1]),
+            ],
+            &RenameOptions::default(),
+        )
+        .unwrap_err();
+        assert!(err.rollback_failures.is_empty());
+
+        // The subtitle file is compressed back
+        assert!(!directory.join("Show.S01E01.srt").exists());
+        let mut decoder = flate2::read::GzDecoder::new(fs::File::open(&compressed_path).unwrap());
+        let mut content = String::new();
+        io::Read::read_to_string(&mut decoder, &mut content).unwrap();
+        assert_eq!(content, "subtitle");
+    }
+
+    #[test]
+    fn empty_file_stem_test() {
+        for subtitle_path in [".srt", "dir/.srt", "..srt", "dir/..SRT"] {
+            assert!(matches!(
+                SubtitleFile::try_from(path::PathBuf::from(subtitle_path)),
+                Err(SubtitleFileError::InvalidSubtileFileName)
+            ));
+        }
+        assert!(MovieFile::new(path::PathBuf::from("dir/..mkv"), None).is_none());
+        assert!(SubtitleFile::try_from(path::PathBuf::from(".hidden.srt")).is_ok());
+    }
+
+    #[test]
+    fn subtitle_file_from_srt_content_test() {
+        let directory = test_directory("from-srt-content");
+        let movie_path = directory.join("Show.S01E02.mkv");
+        let srt_txt_path = directory.join("show s01e02.txt");
+        let notes_txt_path = directory.join("notes.txt");
+        fs::write(&movie_path, "").unwrap();
+        fs::write(&srt_txt_path, "1\n00:00:01,000 --> 00:00:02,000\nHello\n").unwrap();
+        fs::write(&notes_txt_path, "Release notes").unwrap();
+
+        assert!(matches!(
+            SubtitleFile::from_srt_content(notes_txt_path),
+            Err(SubtitleFileError::InvalidSubtileFileName)
+        ));
+
+        let subtitle_file = SubtitleFile::from_srt_content(srt_txt_path.clone()).unwrap();
+        subtitle_file
+            .rename_using_movie_file(&MovieFile::new(movie_path.clone(), None).unwrap())
+            .unwrap();
+
+        assert!(!srt_txt_path.exists());
+        assert!(directory.join("Show.S01E02.srt").exists());
+
+        // A subtitle file already named after the movie file still gets the .srt extension
+        let named_srt_txt_path = directory.join("Show.S01E03.txt");
+        fs::rename(directory.join("Show.S01E02.srt"), &named_srt_txt_path).unwrap();
+        SubtitleFile::from_srt_content(named_srt_txt_path)
+            .unwrap()
+            .rename_using_movie_file(
+                &MovieFile::new(directory.join("Show.S01E03.mkv"), None).unwrap(),
+            )
+            .unwrap();
+        assert!(directory.join("Show.S01E03.srt").exists());
+    }
+
+    #[test]
+    fn rename_nested_subtitle_beside_movie_test() {
+        let directory = test_directory("nested-subtitle");
+        let nested_directory = directory.join("subtitles").join("english");
+        fs::create_dir_all(&nested_directory).unwrap();
+        let movie_path = directory.join("Show.S01E02.mkv");
+        let subtitle_path = nested_directory.join("show s01e02.srt");
+        fs::write(&movie_path, "").unwrap();
+        fs::write(&subtitle_path, "subtitle").unwrap();
+
+        SubtitleFile::try_from(subtitle_path.clone())
+            .unwrap()
+            .rename_using_movie_file(&MovieFile::new(movie_path, None).unwrap())
+            .unwrap();
+
+        // The renamed subtitle file is placed beside the movie file, not in its own directory
+        assert!(!subtitle_path.exists());
+        assert_eq!(
+            fs::read_to_string(directory.join("Show.S01E02.srt")).unwrap(),
+            "subtitle"
+        );
+    }
+
+    #[test]
+    fn matches_test() {
+        let movie_file = MovieFile::new(path::PathBuf::from("Show.S01E02.mkv"), None).unwrap();
+        let matching_subtitle_file =
+            SubtitleFile::try_from(path::PathBuf::from("show s01e02 web.srt")).unwrap();
+        let mismatching_subtitle_file =
+            SubtitleFile::try_from(path::PathBuf::from("show s01e03 web.srt")).unwrap();
+
+        assert!(matching_subtitle_file.matches(&movie_file));
+        assert!(!mismatching_subtitle_file.matches(&movie_file));
+    }
+
+    #[test]
+    fn matched_movie_name_test() {
+        let movie_file =
+            MovieFile::new(path::PathBuf::from("dir/Show.S01E02.720p.mkv"), None).unwrap();
+        let matching_subtitle_file =
+            SubtitleFile::try_from(path::PathBuf::from("dir/show s01e02 web.srt")).unwrap();
+        let mismatching_subtitle_file =
+            SubtitleFile::try_from(path::PathBuf::from("dir/show s01e03 web.srt")).unwrap();
+
+        assert_eq!(
+            matching_subtitle_file
+                .matched_movie_name(&movie_file)
+                .as_deref(),
+            Some("Show.S01E02.720p")
+        );
+        assert_eq!(
+            mismatching_subtitle_file.matched_movie_name(&movie_file),
+            None
+        );
+    }
+
+    #[test]
+    fn rename_on_conflict_test() {
+        for (policy, expected_path, expected_existing) in [
+            (ConflictPolicy::Skip, None, "existing"),
+            (ConflictPolicy::Overwrite, Some("existing.srt"), "subtitle"),
+            (ConflictPolicy::Rename, Some("existing (2).srt"), "existing"),
+        ] {
+            let directory = test_directory(&format!("on-conflict-{:?}", policy));
+            let subtitle_path = directory.join("some subtitle.srt");
+            fs::write(&subtitle_path, "subtitle").unwrap();
+            fs::write(directory.join("existing.srt"), "existing").unwrap();
+            // Making sure the numbering skips the paths that are taken
+            fs::write(directory.join("existing (1).srt"), "taken").unwrap();
+
+            let options = RenameOptions {
+                on_conflict: policy,
+                ..Default::default()
+            };
+            let result = SubtitleFile::try_from(subtitle_path)
+                .unwrap()
+                .rename_to_path_with_options(&directory.join("existing.srt"), &options);
+
+            match expected_path {
+                Some(expected_path) => {
+                    assert_eq!(result.unwrap(), directory.join(expected_path));
+                    assert_eq!(
+                        fs::read_to_string(directory.join(expected_path)).unwrap(),
+                        "subtitle"
+                    );
+                }
+                None => assert!(matches!(result, Err(SubtitleFileError::TargetExists(_)))),
+            }
+            assert_eq!(
+                fs::read_to_string(directory.join("existing.srt")).unwrap(),
+                expected_existing
+            );
+        }
+    }
+
+    #[test]
+    fn planned_path_preserves_movie_casing_test() {
+        let movie_file = MovieFile::new(path::PathBuf::from("Show.S01E02.mkv"), None).unwrap();
+        let subtitle_file =
+            SubtitleFile::try_from(path::PathBuf::from("show.s01e02.web.srt")).unwrap();
+
+        assert_eq!(
+            subtitle_file.planned_path(&movie_file, &RenameOptions::default()),
+            Some(path::PathBuf::from("Show.S01E02.srt"))
+        );
+    }
+
+    #[test]
+    fn new_subtitle_file_path_dots_to_spaces_test() {
+        let movie_file =
+            MovieFile::new(path::PathBuf::from("dir/Breaking.Bad.S01E02.mkv"), None).unwrap();
+        let subtitle_file =
+            SubtitleFile::try_from(path::PathBuf::from("dir/breaking bad s01e02.srt")).unwrap();
+        let options = RenameOptions {
+            dots_to_spaces: true,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            subtitle_file.planned_path(&movie_file, &options),
+            Some(path::PathBuf::from("dir/Breaking Bad S01E02.srt"))
+        );
+    }
+
+    #[test]
+    fn rename_all_or_rollback_test() {
+        let directory = test_directory("rollback");
+        for file_name in [
+            "Show.S01E01.mkv",
+            "show s01e01 web.srt",
+            "Show.S01E02.mkv",
+            "show s01e02 web.srt",
+            "Show.S01E02.srt",
+            "Show.S01E03.mkv",
+            "show s01e03 web.srt",
+        ] {
+            fs::write(directory.join(file_name), file_name).unwrap();
+        }
+
+        let movie_files: Vec<MovieFile> = (1..=3)
+            .map(|episode| {
+                MovieFile::new(directory.join(format!("Show.S01E0{}.mkv", episode)), None).unwrap()
+            })
+            .collect();
+        let subtitle_files: Vec<SubtitleFile> = (1..=3)
+            .map(|episode| {
+                SubtitleFile::try_from(directory.join(format!("show s01e0{} web.srt", episode)))
+                    .unwrap()
+            })
+            .collect();
+        let pairs: Vec<_> = subtitle_files.iter().zip(&movie_files).collect();
+
+        // The second rename fails as its target exists, the first one gets rolled back
+        let err = rename_all_or_rollback(&pairs, &RenameOptions::default()).unwrap_err();
+        assert_eq!(
+            err.subtitle_file_path,
+            directory.join("show s01e02 web.srt")
+        );
+        assert!(matches!(err.source, SubtitleFileError::TargetExists(_)));
+        assert!(err.rollback_failures.is_empty());
+        assert!(directory.join("show s01e01 web.srt").exists());
+        assert!(!directory.join("Show.S01E01.srt").exists());
+        assert!(directory.join("show s01e03 web.srt").exists());
+
+        fs::remove_file(directory.join("Show.S01E02.srt")).unwrap();
+        rename_all_or_rollback(&pairs, &RenameOptions::default()).unwrap();
+        for episode in 1..=3 {
+            assert!(directory
+                .join(format!("Show.S01E0{}.srt", episode))
+                .exists());
+        }
+    }
+
+    #[test]
+    fn planned_path_keeps_modifiers_test() {
+        let movie_file = MovieFile::new(path::PathBuf::from("Show.S01E02.mkv"), None).unwrap();
+
+        for (subtitle_file_name, new_subtitle_file_name) in [
+            ("show s01e02 web.forced.srt", "Show.S01E02.forced.srt"),
+            ("show s01e02 web.sdh.srt", "Show.S01E02.sdh.srt"),
+            ("show s01e02 web.srt", "Show.S01E02.srt"),
+            // Duplicate downloads
+            ("show s01e02 web (1).srt", "Show.S01E02.srt"),
+            ("show s01e02 web.forced (2).srt", "Show.S01E02.forced.srt"),
+        ] {
+            let subtitle_file =
+                SubtitleFile::try_from(path::PathBuf::from(subtitle_file_name)).unwrap();
+            assert_eq!(
+                subtitle_file.planned_path(&movie_file, &RenameOptions::default()),
+                Some(path::PathBuf::from(new_subtitle_file_name))
+            );
+        }
+    }
+
+    #[test]
+    fn extension_case_test() {
+        let movie_path = path::PathBuf::from("Show.S01E02.MKV");
+        let subtitle_path = path::PathBuf::from("Show.S01E02.SRT");
+
+        assert!(MovieFile::new(movie_path.clone(), None).is_some());
+        assert!(MovieFile::new_with_extension_case(movie_path.clone(), None, false).is_none());
+        assert!(MovieFile::new_with_extension_case(
+            path::PathBuf::from("Show.S01E02.MPG"),
+            Some(&vec!["mpg".to_string()]),
+            true
+        )
+        .is_some());
+
+        assert!(SubtitleFile::try_from(subtitle_path.clone()).is_ok());
+        assert!(SubtitleFile::try_from_with_extension_case(subtitle_path, false).is_err());
+        assert!(SubtitleFile::try_from_with_extension_case(
+            path::PathBuf::from("Show.S01E02.srt"),
+            false
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn new_subtitle_file_path_trim_after_signature_test() {
+        let movie_file =
+            MovieFile::new(path::PathBuf::from("Show.S01E02.The.Title.mkv"), None).unwrap();
+        let subtitle_file = SubtitleFile::try_from(path::PathBuf::from("Show.S01E02.srt")).unwrap();
+        let options = RenameOptions {
+            trim_after_signature: true,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            subtitle_file.planned_path(&movie_file, &options),
+            Some(path::PathBuf::from("Show.S01E02.srt"))
+        );
+        assert_eq!(
+            subtitle_file.planned_path(&movie_file, &RenameOptions::default()),
+            Some(path::PathBuf::from("Show.S01E02.The.Title.srt"))
+        );
+    }
+
+    #[test]
+    fn new_subtitle_file_path_normalize_signature_test() {
+        let movie_file =
+            MovieFile::new(path::PathBuf::from("dir/Show S01 E02 The Title.mkv"), None).unwrap();
+        let subtitle_file =
+            SubtitleFile::try_from(path::PathBuf::from("dir/show s01e02 web.srt")).unwrap();
+        let options = RenameOptions {
+            normalize_signature: true,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            subtitle_file.planned_path(&movie_file, &options),
+            Some(path::PathBuf::from("dir/Show S01E02 The Title.srt"))
+        );
+        assert_eq!(
+            subtitle_file.planned_path(&movie_file, &RenameOptions::default()),
+            Some(path::PathBuf::from("dir/Show S01 E02 The Title.srt"))
+        );
+    }
+
+    #[test]
+    fn new_subtitle_file_path_plex_target_test() {
+        let options = RenameOptions {
+            target: Some(Target::Plex),
+            ..Default::default()
+        };
+        let subtitle_file =
+            SubtitleFile::try_from(path::PathBuf::from("dir/breaking.bad.s01e02.web.en.srt"))
+                .unwrap();
+
+        for (movie_name, new_subtitle_name) in [
+            (
+                "dir/Breaking.Bad.2008.S01E02.720p.WEB-GRP.mkv",
+                "dir/Breaking Bad (2008) - s01e02.srt",
+            ),
+            (
+                "dir/Breaking Bad (2008) - s01e02 - Cat's in the Bag.mkv",
+                "dir/Breaking Bad (2008) - s01e02 - Cat's in the Bag.srt",
+            ),
+        ] {
+            let movie_file = MovieFile::new(path::PathBuf::from(movie_name), None).unwrap();
+            assert_eq!(
+                subtitle_file.planned_path(&movie_file, &options),
+                Some(path::PathBuf::from(new_subtitle_name))
+            );
+        }
+    }
+
+    #[test]
+    fn new_subtitle_file_path_default_season_test() {
+        let movie_file = MovieFile::new(path::PathBuf::from("dir/Show.E02.mkv"), None).unwrap();
+        let subtitle_file =
+            SubtitleFile::try_from(path::PathBuf::from("dir/show e02 web.srt")).unwrap();
+        let options = RenameOptions {
+            default_season: Some(1),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            subtitle_file.planned_path(&movie_file, &options),
+            Some(path::PathBuf::from("dir/Show.S01E02.srt"))
+        );
+        assert_eq!(
+            subtitle_file.planned_path(&movie_file, &RenameOptions::default()),
+            None
+        );
+    }
+
+    #[test]
+    fn new_subtitle_file_path_prefix_suffix_test() {
+        let movie_file = MovieFile::new(path::PathBuf::from("dir/Show.s01e02.mkv"), None).unwrap();
+        let subtitle_file =
+            SubtitleFile::try_from(path::PathBuf::from("dir/show s01e02 web.forced.srt")).unwrap();
+
+        let new_subtitle_file_path = |prefix: &str, suffix: &str| {
+            let options = RenameOptions {
+                prefix: prefix.to_string(),
+                suffix: suffix.to_string(),
+                ..Default::default()
+            };
+            subtitle_file.new_subtitle_file_path(&movie_file, &options)
+        };
+
+        assert_eq!(
+            new_subtitle_file_path("[SUB] ", ""),
+            path::PathBuf::from("dir/[SUB] Show.s01e02.forced.srt")
+        );
+        assert_eq!(
+            new_subtitle_file_path("", ".tagged"),
+            path::PathBuf::from("dir/Show.s01e02.tagged.forced.srt")
+        );
+        assert_eq!(
+            new_subtitle_file_path("[SUB] ", ".tagged"),
+            path::PathBuf::from("dir/[SUB] Show.s01e02.tagged.forced.srt")
+        );
+    }
+
+    #[test]
+    fn new_subtitle_file_path_mpv_test() {
+        // mpv loads "<movie stem>.srt" and "<movie stem>.<suffix>.srt" subtitle files
+        let is_mpv_loadable = |subtitle_path: &path::Path, movie_stem: &str| {
+            let subtitle_name = subtitle_path.file_name().unwrap().to_str().unwrap();
+            let Some(rest) = subtitle_name.strip_prefix(movie_stem) else {
+                return false;
+            };
+            match rest.strip_suffix(".srt") {
+                Some("") => true,
+                Some(suffix) => suffix
+                    .strip_prefix('.')
+                    .is_some_and(|suffix| suffix.chars().all(|x| x.is_ascii_lowercase())),
+                None => false,
+            }
+        };
+
+        let movie_file =
+            MovieFile::new(path::PathBuf::from("dir/Breaking.Bad.S01E02-GRP.mkv"), None).unwrap();
+        let options = RenameOptions {
+            player: Some(Player::Mpv),
+            lowercase_output: true,
+            dots_to_spaces: true,
+            strip_release_group: true,
+            ..Default::default()
+        };
+
+        for (subtitle_name, new_subtitle_name) in [
+            ("dir/breaking bad s01e02.srt", "Breaking.Bad.S01E02-GRP.srt"),
+            (
+                "dir/breaking bad s01e02.forced.SDH.srt",
+                "Breaking.Bad.S01E02-GRP.sdh.srt",
+            ),
+            (
+                "dir/breaking bad s01e02.en.forced.srt",
+                "Breaking.Bad.S01E02-GRP.en.srt",
+            ),
+        ] {
+            let new_subtitle_path = SubtitleFile::try_from(path::PathBuf::from(subtitle_name))
+                .unwrap()
+                .new_subtitle_file_path(&movie_file, &options);
+
+            assert_eq!(
+                new_subtitle_path,
+                path::Path::new("dir").join(new_subtitle_name)
+            );
+            assert!(is_mpv_loadable(
+                &new_subtitle_path,
+                "Breaking.Bad.S01E02-GRP"
+            ));
+        }
+    }
+
+    #[test]
+    fn planned_path_different_titles_test() {
+        let movie_file =
+            MovieFile::new(path::PathBuf::from("dir/Breaking.Bad.S01E01.mkv"), None).unwrap();
+        let subtitle_file =
+            SubtitleFile::try_from(path::PathBuf::from("dir/Better Call Saul s01e01.srt")).unwrap();
+
+        assert_eq!(
+            subtitle_file.planned_path(&movie_file, &RenameOptions::default()),
+            None
+        );
+        assert_eq!(
+            subtitle_file.planned_path(
+                &movie_file,
+                &RenameOptions {
+                    ignore_titles: true,
+                    ..Default::default()
+                }
+            ),
+            Some(path::PathBuf::from("dir/Breaking.Bad.S01E01.srt"))
+        );
+    }
+
+    #[test]
+    fn planned_path_release_group_test() {
+        let movie_file =
+            MovieFile::new(path::PathBuf::from("dir/[Group] Show.S01E02.mkv"), None).unwrap();
+        let subtitle_file =
+            SubtitleFile::try_from(path::PathBuf::from("dir/Show.S01E02.srt")).unwrap();
+        assert_eq!(
+            subtitle_file.planned_path(&movie_file, &RenameOptions::default()),
+            Some(path::PathBuf::from("dir/[Group] Show.S01E02.srt"))
+        );
+
+        let movie_file = MovieFile::new(
+            path::PathBuf::from("dir/[SubsPlease] Show - 27 [1080p].mkv"),
+            None,
+        )
+        .unwrap();
+        let subtitle_file =
+            SubtitleFile::try_from(path::PathBuf::from("dir/Show - 27.srt")).unwrap();
+        let options = RenameOptions {
+            episode_map: HashMap::from([(27, Signature::parse("S02E05").unwrap())]),
+            ..Default::default()
+        };
+        assert_eq!(
+            subtitle_file.planned_path(&movie_file, &options),
+            Some(path::PathBuf::from(
+                "dir/[SubsPlease] Show - 27 [1080p].srt"
+            ))
+        );
+    }
+
+    #[test]
+    fn copy_using_movie_file_test() {
+        let directory = test_directory("copy-as");
+        fs::write(directory.join("Show.S01E02.mkv"), "").unwrap();
+        fs::write(directory.join("show s01e02 web.srt"), "subtitle").unwrap();
+
+        let movie_file = MovieFile::new(directory.join("Show.S01E02.mkv"), None).unwrap();
+        let subtitle_file = SubtitleFile::try_from(directory.join("show s01e02 web.srt")).unwrap();
+        let options = RenameOptions {
+            copy_as: vec!["srt".to_string(), ".sub".to_string()],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            subtitle_file
+                .copy_using_movie_file_with_options(&movie_file, &options)
+                .unwrap(),
+            vec![
+                directory.join("Show.S01E02.srt"),
+                directory.join("Show.S01E02.sub")
+            ]
+        );
+        assert_eq!(
+            fs::read_to_string(directory.join("Show.S01E02.sub")).unwrap(),
+            "subtitle"
+        );
+        assert!(directory.join("show s01e02 web.srt").exists());
+
+        // Copying again fails as the copies exist
+        assert!(matches!(
+            subtitle_file.copy_using_movie_file_with_options(&movie_file, &options),
+            Err(SubtitleFileError::TargetExists(_))
+        ));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn planned_path_non_utf8_name_test() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        let movie_file = MovieFile::new(
+            path::PathBuf::from(OsStr::from_bytes(b"Show\xff.S01E02.mkv")),
+            None,
+        )
+        .unwrap();
+        let subtitle_file = SubtitleFile::try_from(path::PathBuf::from("show s01e02.srt")).unwrap();
+        let options = RenameOptions {
+            lowercase_output: true,
+            ..Default::default()
+        };
+
+        // The invalid byte is kept, the name isn't transformed rather than corrupted
+        assert_eq!(
+            subtitle_file.planned_path(&movie_file, &options),
+            Some(path::PathBuf::from(OsStr::from_bytes(
+                b"Show\xff.S01E02.srt"
+            )))
+        );
+    }
 }
@@ -1,106 +1,955 @@
 use anyhow::{bail, Result};
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use colored::*;
+use regex::Regex;
+use report::{
+    MatchedPair, OutputFormat, RenameRecord, RenameStatus, RunResults, RunStats, RunSummary,
+};
+use std::collections::{HashMap, HashSet};
 use std::fs;
+use std::io::{self, Write};
+use std::panic;
 use std::path;
+use std::time::Duration;
 use sub_auto_rename::*;
 
+mod crc_list;
+mod episode_map;
+mod plan;
+mod report;
+mod streaming;
+#[cfg(test)]
+mod test_directory;
+#[cfg(feature = "watch")]
+mod watch;
+
+const PER_MOVIE_SUBTITLES_DIRECTORY: &str = "subs";
+const TXT_FILE_EXTENSION: &str = "txt";
+const SUBTITLE_EXTENSION: &str = "srt";
+/// The file of the scanned directory listing gitignore style patterns of the paths to exclude
+const IGNORE_FILE_NAME: &str = ".sarignore";
+
 #[derive(Parser)]
-#[command(author, version, about)]
+#[command(author, version, about, subcommand_negates_reqs = true)]
 struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// The directory where there are all the episodes and
     /// and their corresponding subtitle files
-    episodes_subs_directory: path::PathBuf,
+    #[arg(required_unless_present = "print_regex")]
+    episodes_subs_directory: Option<path::PathBuf>,
 
     /// Extra movie extensions to include when checking movie files in a directory
+    #[clap(env = "SAR_EXTRA_EXTENSIONS", value_delimiter = ',')]
     extra_movie_extensions: Option<Vec<String>>,
 
     /// Whether to ignore the difference in the number of files between subtitle files
     /// and episodes files as the default behaviour expects them to be of equal amount.
-    #[clap(short, long)]
+    #[clap(short, long, env = "SAR_IGNORE_NUMBER_DIFFERENCE")]
     ignore_number_difference: bool,
 
+    /// Whether to only warn about a difference in the number of files between subtitle files
+    /// and episode files, instead of stopping with an error.
+    #[clap(long, conflicts_with = "ignore_number_difference")]
+    warn_count_mismatch: bool,
+
     /// Whether to get a summary of renamed and non-renamed subtitle files after rename completes.
-    #[clap(short, long)]
+    #[clap(short, long, env = "SAR_SUMMARIZE")]
     summarize: bool,
+
+    /// Whether to lowercase the names of the renamed subtitle files.
+    #[clap(long)]
+    lowercase_output: bool,
+
+    /// Whether to keep a backup copy (name.srt.bak) of the subtitle files before renaming them.
+    #[clap(long)]
+    keep_original: bool,
+
+    /// Copy the subtitle files next to their movie files instead of renaming them.
+    #[clap(long)]
+    copy: bool,
+
+    /// The extension of the copies made with --copy, repeat it to make one copy per extension,
+    /// like --as srt --as sub. Defaults to srt.
+    #[clap(long = "as", requires = "copy")]
+    copy_as: Vec<String>,
+
+    /// Only print a summary counting the renamed, skipped and unmatched files once the run
+    /// completes, without any per file output.
+    #[clap(long)]
+    summary_only: bool,
+
+    /// Only print the problems once the run completes, that is the unmatched movie files and
+    /// subtitle files and the failed renames, without any line for the files that went fine.
+    #[clap(long, conflicts_with = "summary_only")]
+    report_unmatched: bool,
+
+    /// Print what happened to the files grouped by the title of their show once the run
+    /// completes, for directories holding several shows.
+    #[clap(long)]
+    group_by_show: bool,
+
+    /// Whether to print statistics of the run after rename completes.
+    #[clap(long)]
+    stats: bool,
+
+    /// The format of the output, the json format only prints the machine readable reports.
+    #[clap(long, value_enum, default_value_t, env = "SAR_FORMAT")]
+    format: OutputFormat,
+
+    /// Only print the matched movie and subtitle files pairs without renaming anything.
+    #[clap(long, conflicts_with = "streaming")]
+    match_only: bool,
+
+    /// Only process files modified within the given duration, like 30m, 12h, 2d or 1w.
+    #[clap(long, value_parser = parse_duration)]
+    since: Option<Duration>,
+
+    /// Whether to strip a trailing release group tag, like "-RARBG" or "[EtHD]", from the
+    /// names of the renamed subtitle files.
+    #[clap(long)]
+    strip_release_group: bool,
+
+    /// Whether to match files having clearly different titles, like "Breaking.Bad.S01E01.mkv"
+    /// and "Better Call Saul S01E01.srt", which are told apart by default.
+    #[clap(long)]
+    ignore_titles: bool,
+
+    /// Whether to ignore the case when comparing the titles of the files, so that
+    /// "breaking.bad" and "Breaking.Bad" are the same title. Pass false to tell them apart.
+    #[clap(long, default_value_t = true, action = clap::ArgAction::Set)]
+    case_insensitive_match: bool,
+
+    /// Whether to drop everything after the signature in the renamed subtitle file names, like
+    /// the episode title of "Show.S01E02.The.Title.mkv".
+    #[clap(long)]
+    trim_after_signature: bool,
+
+    /// Whether to remove the separators between the season and the episode of the signatures
+    /// in the renamed subtitle file names, like "S01E02" for "S01 E02".
+    #[clap(long)]
+    normalize_signature: bool,
+
+    /// Whether to replace the dots separating the words of the renamed subtitle file names
+    /// with spaces, like "Breaking Bad S01E02.srt".
+    #[clap(long)]
+    dots_to_spaces: bool,
+
+    /// Text to put before the stem of the renamed subtitle file names, like "[SUB] ".
+    #[clap(long, default_value = "")]
+    prefix: String,
+
+    /// Text to put after the stem of the renamed subtitle file names, before the extension.
+    #[clap(long, default_value = "")]
+    suffix: String,
+
+    /// How many times to retry renaming a subtitle file when it fails with a transient
+    /// filesystem error, like a busy file on a network mount.
+    #[clap(long, default_value_t = 0, env = "SAR_RETRIES")]
+    retries: u32,
+
+    /// Ask for confirmation before renaming when more than this number of renames are planned.
+    #[clap(long, default_value_t = 50, env = "SAR_CONFIRM_THRESHOLD")]
+    confirm_threshold: usize,
+
+    /// Don't ask for any confirmation, assuming yes as the answer, so that scripts never wait
+    /// for an answer. Applies to the subcommands as well.
+    #[clap(short, long, alias = "assume-yes", global = true, env = "SAR_YES")]
+    yes: bool,
+
+    /// Whether to stop with an error, instead of just warning, when multiple movie files
+    /// share the same signature.
+    #[clap(long)]
+    strict: bool,
+
+    /// Write the planned renames to this file, as "old<TAB>new" lines, instead of renaming.
+    /// The plan can be reviewed or edited and then carried out with the apply subcommand.
+    #[clap(long, conflicts_with = "streaming")]
+    plan_file: Option<path::PathBuf>,
+
+    /// Print more details of what is being done, -v for debug messages and -vv for every
+    /// comparison between movie and subtitle file names.
+    #[clap(short, long, action = clap::ArgAction::Count)]
+    verbose: u8,
+
+    /// Extensions of sidecar files, like nfo or chapters.xml, sharing the stem of a subtitle
+    /// file to rename alongside it.
+    #[clap(long, value_delimiter = ',')]
+    rename_sidecars: Vec<String>,
+
+    /// Movie file extensions to leave out, even default ones, like 3gp.
+    #[clap(long, value_delimiter = ',')]
+    exclude_ext: Vec<String>,
+
+    /// Whether to also look for movie and subtitle files in the subdirectories of the directory.
+    #[clap(short, long, env = "SAR_RECURSIVE")]
+    recursive: bool,
+
+    /// How deep to look for files in the subdirectories of the directory, 0 being the directory
+    /// itself only. Implies --recursive.
+    #[clap(long, env = "SAR_MAX_DEPTH")]
+    max_depth: Option<usize>,
+
+    /// A file mapping absolute episode numbers to signatures, with lines like "27,S02E05", to
+    /// match names numbering the episodes continuously across seasons.
+    #[clap(long)]
+    episode_map: Option<path::PathBuf>,
+
+    /// Whether to move existing files in the way of renamed subtitle files to the trash,
+    /// instead of skipping those subtitle files.
+    #[clap(long)]
+    trash: bool,
+
+    /// Write a CSV report of what happened to every movie and subtitle file to this file.
+    #[clap(long)]
+    report: Option<path::PathBuf>,
+
+    /// Write the JSON results of the run to this file, whatever the format of the output.
+    #[clap(long)]
+    summary_json_to: Option<path::PathBuf>,
+
+    /// Append a JSON line to this file for every rename as it happens, failed and skipped ones
+    /// included, for tailing the renames of long runs or watched directories.
+    #[clap(long, conflicts_with = "streaming")]
+    rename_log: Option<path::PathBuf>,
+
+    /// Whether to check the content of .txt files and treat the ones looking like SRT as
+    /// subtitle files, which get the .srt extension when renamed.
+    #[clap(long)]
+    sniff_txt: bool,
+
+    /// Whether to read seasons written in Roman numerals, like "Season IV" or "S.IV".
+    #[clap(long)]
+    roman_numeral_seasons: bool,
+
+    /// Whether to read volumes, like "Vol.1" or "Volume 1", as seasons, so that "Vol.1 E02"
+    /// matches S01E02.
+    #[clap(long)]
+    volume_seasons: bool,
+
+    /// Extra markers of seasons for non-English names, like t for the Spanish "temporada" in
+    /// "T01C02". Markers longer than a character may be separated from the number.
+    #[clap(long, value_delimiter = ',')]
+    season_marker: Vec<String>,
+
+    /// Extra markers of episodes for non-English names, like c for the Spanish "capítulo" in
+    /// "T01C02".
+    #[clap(long, value_delimiter = ',')]
+    episode_marker: Vec<String>,
+
+    /// Experimental, a number added to the episode of the subtitle files when matching them
+    /// with movie files, for subtitle packs numbering the episodes off by one, like 1 or -1.
+    #[clap(long, default_value_t = 0, allow_negative_numbers = true)]
+    episode_offset: i32,
+
+    /// Whether to read four digits codes like "0102" as signatures, like S01E02, when file
+    /// names have no other signature.
+    #[clap(long)]
+    numeric_code: bool,
+
+    /// Whether to read "#" numbers, like the "#102" of "Show #102", as absolute episode
+    /// numbers, matching files without signatures having the same number.
+    #[clap(long)]
+    hash_episodes: bool,
+
+    /// The season of the file names only having an episode, like "Show.E02" for miniseries,
+    /// which is written in the names of the renamed subtitle files, like "Show.S01E02.srt".
+    #[clap(long)]
+    default_season: Option<u32>,
+
+    /// A regex with "season" and "episode" named groups reading the signatures of the file
+    /// names instead of the built-in scanner, like 'Vol(?P<season>\d+)-Part(?P<episode>\d+)'.
+    #[clap(long, value_parser = parse_pattern)]
+    pattern: Option<Regex>,
+
+    /// Print how the signatures of the file names are read, that is the --pattern regex or the
+    /// rules of the built-in scanner along with the enabled options, without renaming anything.
+    #[clap(long)]
+    print_regex: bool,
+
+    /// What to do when a renamed subtitle file would take the path of an existing file,
+    /// --trash takes precedence.
+    #[clap(long, value_enum, default_value_t)]
+    on_conflict: ConflictPolicy,
+
+    /// Name the subtitle files the way this player loads them, only keeping the stem of the
+    /// movie file and a single suffix like ".forced", whatever the other name options.
+    #[clap(long, value_enum)]
+    player: Option<Player>,
+
+    /// Name the subtitle files following the naming conventions of this media server, like
+    /// "Show Name - s01e02.srt" for Plex, whatever the names of the movie files.
+    #[clap(long, value_enum)]
+    target: Option<Target>,
+
+    /// Rename the subtitle files as soon as their movie files are found instead of collecting
+    /// all the files first, keeping the memory use low on huge directories. Each file is
+    /// paired with the first file found matching it, most of the checks and reports are not
+    /// available.
+    #[clap(long)]
+    streaming: bool,
+
+    /// The order in which the files are processed, the first subtitle file winning when several
+    /// of them match a movie file equally well.
+    #[clap(long, value_enum, default_value_t, conflicts_with = "streaming")]
+    sort: SortKey,
+
+    /// Skip the movie files that already have a subtitle file named after them beside them.
+    #[clap(long)]
+    only_missing: bool,
+
+    /// Whether to ignore the case of file extensions, so that ".SRT" files are subtitle files
+    /// too. Pass false to only find files with exactly the expected extensions.
+    #[clap(long, default_value_t = true, action = clap::ArgAction::Set)]
+    ignore_extension_case: bool,
+
+    /// Only report what would be renamed without renaming anything.
+    #[clap(long, conflicts_with = "streaming")]
+    dry_run: bool,
+
+    /// Pair the movie files and subtitle files by their position in the order of their paths,
+    /// ignoring signatures, for files only numbered like "1.mkv" and "1 English.srt".
+    #[clap(long, conflicts_with = "streaming")]
+    rename_subtitles_to_index: bool,
+
+    /// A hash list with lines like "ABCD1234,27", pairing each movie file with the subtitle
+    /// file of the episode its CRC32 is listed with, for anime releases checked against a list.
+    /// The subtitle files are numbered like "Show - 27.srt" or "Show S02E27.srt".
+    #[clap(long, conflicts_with_all = ["rename_subtitles_to_index", "streaming"])]
+    crc_list: Option<path::PathBuf>,
+
+    /// Rename the zip archives holding a single subtitle file, along with the subtitle file
+    /// inside them, after their movie files without extracting them.
+    #[cfg(feature = "zip")]
+    #[clap(long, conflicts_with_all = ["streaming", "plan_file"])]
+    zipped_subtitles: bool,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Renames a single subtitle file using the name of a single movie file
+    Pair {
+        /// The movie file to take the name from
+        #[arg(long)]
+        movie: path::PathBuf,
+
+        /// The subtitle file to rename
+        #[arg(long)]
+        subtitle: path::PathBuf,
+
+        /// Whether to rename even when the movie file and subtitle file signatures don't match
+        #[arg(short, long)]
+        force: bool,
+
+        /// The signature of the subtitle file, like "S03E04", whatever its name. The subtitle
+        /// file is only renamed when the movie file has this signature
+        #[arg(long, value_parser = parse_signature, conflicts_with = "force")]
+        signature: Option<Signature>,
+    },
+    /// Carries out the renames of a plan file written with the --plan-file option
+    Apply {
+        /// The plan file to carry out
+        #[arg(long)]
+        plan_file: path::PathBuf,
+    },
+    /// Writes a copy of a tar archive of movie and subtitle files with the subtitle files
+    /// renamed after the movie files they match
+    #[cfg(feature = "tar")]
+    Tar {
+        /// The tar archive to read the movie and subtitle files from, which is left alone
+        #[arg(long)]
+        archive: path::PathBuf,
+
+        /// The path of the copy of the archive with the subtitle files renamed
+        #[arg(long)]
+        output: path::PathBuf,
+    },
+    /// Keeps watching a directory, like a download directory, renaming the subtitle files after
+    /// the movie files as they land. Files are handled once they stopped changing for a while,
+    /// using the options of the command line, --yes being assumed
+    #[cfg(feature = "watch")]
+    Watch {
+        /// The directory to watch
+        directory: path::PathBuf,
+
+        /// How long files have to be left alone before being handled, like 10s or 1m
+        #[arg(long, default_value = "5s", value_parser = parse_duration)]
+        settle: Duration,
+    },
 }
 
 fn main() -> Result<()> {
     let cli = Cli::parse();
 
-    simple_logger::init()?;
+    simple_logger::SimpleLogger::new()
+        .with_level(log_level(&cli))
+        .init()?;
 
-    let mut movie_files = Vec::new();
-    let mut subtitle_files = Vec::new();
+    let rename_options = RenameOptions {
+        lowercase_output: cli.lowercase_output,
+        keep_original: cli.keep_original,
+        copy_as: match (cli.copy, cli.copy_as.is_empty()) {
+            (true, true) => vec![SUBTITLE_EXTENSION.to_string()],
+            _ => cli.copy_as.clone(),
+        },
+        strip_release_group: cli.strip_release_group,
+        dots_to_spaces: cli.dots_to_spaces,
+        prefix: cli.prefix.clone(),
+        suffix: cli.suffix.clone(),
+        trim_after_signature: cli.trim_after_signature,
+        normalize_signature: cli.normalize_signature,
+        ignore_titles: cli.ignore_titles,
+        case_sensitive_titles: !cli.case_insensitive_match,
+        retries: cli.retries,
+        sidecar_extensions: cli.rename_sidecars.clone(),
+        episode_map: match &cli.episode_map {
+            Some(episode_map) => {
+                episode_map::read_episode_map(io::BufReader::new(fs::File::open(episode_map)?))?
+            }
+            None => HashMap::new(),
+        },
+        trash_existing_target: cli.trash,
+        roman_numeral_seasons: cli.roman_numeral_seasons,
+        volume_seasons: cli.volume_seasons,
+        season_markers: cli.season_marker.clone(),
+        episode_markers: cli.episode_marker.clone(),
+        episode_offset: cli.episode_offset,
+        numeric_code: cli.numeric_code,
+        hash_episodes: cli.hash_episodes,
+        default_season: cli.default_season,
+        pattern: cli.pattern.clone(),
+        on_conflict: cli.on_conflict,
+        player: cli.player,
+        target: cli.target,
+        // Pairs made by position or by hash don't have signatures to check
+        force: cli.rename_subtitles_to_index || cli.crc_list.is_some(),
+    };
 
-    for dir_entry in fs::read_dir(cli.episodes_subs_directory)? {
-        let dir_entry = match dir_entry {
-            Ok(dir_entry) => dir_entry,
-            Err(err) => {
-                log::error!("Error reading a directory entry: {}", err);
-                continue;
+    if cli.print_regex {
+        print!("{}", describe_matching(&cli));
+        return Ok(());
+    }
+
+    match &cli.command {
+        Some(Command::Pair {
+            movie,
+            subtitle,
+            force,
+            signature,
+        }) => rename_pair(
+            &cli,
+            movie,
+            subtitle,
+            *signature,
+            &RenameOptions {
+                force: *force,
+                ..rename_options
+            },
+        ),
+        Some(Command::Apply { plan_file }) => {
+            let renamed = apply_plan(plan_file, &rename_options)?;
+            println!(
+                "\n{}",
+                format!("Renamed subtitle files : {}", renamed).blue()
+            );
+            Ok(())
+        }
+        #[cfg(feature = "tar")]
+        Some(Command::Tar { archive, output }) => {
+            let renamed_entries = rename_tar_entries(archive, output, &rename_options)?;
+            for (old_path, new_path) in &renamed_entries {
+                println!(
+                    "{} Renamed subtitle file '{}' to '{}'",
+                    "->".green(),
+                    old_path.display(),
+                    new_path.display()
+                );
             }
-        };
+            println!(
+                "\n{}",
+                format!("Renamed subtitle files : {}", renamed_entries.len()).blue()
+            );
+            Ok(())
+        }
+        #[cfg(feature = "watch")]
+        Some(Command::Watch { directory, settle }) => {
+            let directory = validate_directory(directory)?;
+            log::info!("Watching '{}' for new files", directory.display());
+            watch::watch_directory(
+                &directory,
+                cli.recursive || cli.max_depth.is_some(),
+                *settle,
+                || rename_directory(&cli, &directory, &RunOptions::watch(), &rename_options),
+            )
+        }
+        None => rename_directory(
+            &cli,
+            cli.episodes_subs_directory
+                .as_deref()
+                .expect("clap requires the directory when there is no subcommand"),
+            &RunOptions::from_cli(&cli),
+            &rename_options,
+        ),
+    }
+}
 
-        if let Some(movie_file) =
-            MovieFile::new(dir_entry.path(), cli.extra_movie_extensions.as_ref())
-        {
-            movie_files.push(movie_file);
-            continue;
-        };
+/// Checks that the path is an existing directory, returning it without trailing separators so
+/// that "videos/" and "videos" are handled the same way
+fn validate_directory(directory: &path::Path) -> Result<path::PathBuf> {
+    match fs::metadata(directory) {
+        Ok(metadata) if metadata.is_dir() => Ok(directory.components().collect()),
+        Ok(_) => bail!(
+            "Expected a directory, got a file: '{}'",
+            directory.display()
+        ),
+        Err(err) => bail!(
+            "Can't read the directory '{}': {}",
+            directory.display(),
+            err
+        ),
+    }
+}
 
-        if let Ok(subtitle_file) = SubtitleFile::try_from(dir_entry.path()) {
-            subtitle_files.push(subtitle_file);
-        };
+/// Returns the maximum level of the log messages to print for the number of verbose flags. Only
+/// errors are printed by default when only the summary or the problems are asked for
+fn log_level(cli: &Cli) -> log::LevelFilter {
+    match cli.verbose {
+        0 if cli.summary_only || cli.report_unmatched => log::LevelFilter::Error,
+        0 => log::LevelFilter::Info,
+        1 => log::LevelFilter::Debug,
+        _ => log::LevelFilter::Trace,
+    }
+}
+
+/// Renames a single subtitle file using the name of a single movie file. When given, the
+/// signature stands for the one of the subtitle file, which is only renamed when the movie file
+/// has that signature
+fn rename_pair(
+    cli: &Cli,
+    movie: &path::Path,
+    subtitle: &path::Path,
+    signature: Option<Signature>,
+    rename_options: &RenameOptions,
+) -> Result<()> {
+    let Some(movie_file) = MovieFile::new(movie.into(), cli.extra_movie_extensions.as_ref()) else {
+        bail!("'{}' is not a movie file", movie.display());
+    };
+    let subtitle_file = SubtitleFile::try_from(path::PathBuf::from(subtitle))?;
+
+    let forced_options;
+    let rename_options = match signature {
+        Some(signature) => {
+            if movie_file.match_key(rename_options) != Some(MatchKey::Signature(signature)) {
+                bail!("'{}' is not the {} episode", movie_file, signature);
+            }
+            // The signature of the subtitle file has been checked already
+            forced_options = RenameOptions {
+                force: true,
+                ..rename_options.clone()
+            };
+            &forced_options
+        }
+        None => rename_options,
+    };
+
+    match subtitle_file.rename_using_movie_file_with_options(&movie_file, rename_options)? {
+        RenameOutcome::Moved(_) => {
+            println!("{} Renamed subtitle file '{}'", "->".green(), subtitle_file)
+        }
+        RenameOutcome::AlreadyCorrect => println!(
+            "{} Subtitle file '{}' is already named after the movie file",
+            "->".yellow(),
+            subtitle_file
+        ),
+    }
+
+    Ok(())
+}
+
+/// What to do when there are not as many subtitle files as movie files
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CountMismatch {
+    /// Stopping with an error
+    Fail,
+    /// Only warning about it
+    Warn,
+    /// Going on without a word
+    Ignore,
+}
+
+/// Options of a run over a directory which depend on the mode the program runs in rather than
+/// only on the command line
+#[derive(Debug, Clone, Copy)]
+struct RunOptions {
+    /// Renaming without asking for confirmation
+    assume_yes: bool,
+    /// What to do when the numbers of movie and subtitle files differ
+    count_mismatch: CountMismatch,
+    /// Skipping the movie files that already have a subtitle file named after them
+    only_missing: bool,
+}
+
+impl RunOptions {
+    /// Returns the run options set on the command line
+    fn from_cli(cli: &Cli) -> Self {
+        Self {
+            assume_yes: cli.yes,
+            count_mismatch: if cli.ignore_number_difference {
+                CountMismatch::Ignore
+            } else if cli.warn_count_mismatch {
+                CountMismatch::Warn
+            } else {
+                CountMismatch::Fail
+            },
+            only_missing: cli.only_missing,
+        }
+    }
+
+    /// Returns the run options of a watched directory. Nobody is there to confirm the renames,
+    /// files land one at a time so the counts differ most of the time, and the movie files
+    /// subtitled by earlier runs are left alone
+    #[cfg(feature = "watch")]
+    fn watch() -> Self {
+        Self {
+            assume_yes: true,
+            count_mismatch: CountMismatch::Ignore,
+            only_missing: true,
+        }
+    }
+}
+
+/// Renames all the subtitle files in the directory using the names of the movie files
+/// found in the same directory
+fn rename_directory(
+    cli: &Cli,
+    directory: &path::Path,
+    run_options: &RunOptions,
+    rename_options: &RenameOptions,
+) -> Result<()> {
+    let directory = &validate_directory(directory)?;
+
+    if cli.streaming {
+        let mut streaming_renamer =
+            streaming::StreamingRenamer::new(rename_options, cli.format == OutputFormat::Human);
+        scan_files(directory, &ScanOptions::from_cli(cli), |scanned_file| {
+            streaming_renamer.add(scanned_file)
+        })?;
+
+        let stats = streaming_renamer.finish();
+        if cli.stats {
+            stats.print(cli.format);
+        }
+        return Ok(());
     }
 
-    if !cli.ignore_number_difference && movie_files.len() != subtitle_files.len() {
-        bail!(
-            "Total movie files are not the same as total subtitle files. Movies: {}, Subtitles: {}",
-            movie_files.len(),
-            subtitle_files.len(),
+    let (mut movie_files, mut subtitle_files) =
+        collect_files(directory, &ScanOptions::from_cli(cli))?;
+    sort_files(&mut movie_files, MovieFile::path, cli.sort);
+    sort_files(&mut subtitle_files, SubtitleFile::path, cli.sort);
+
+    // Printed whatever the verbosity, as it tells how well the files are named
+    if prints_progress(cli) {
+        let (detected_signatures, scanned_files) =
+            count_detected_signatures(&movie_files, &subtitle_files, rename_options);
+        println!(
+            "{}",
+            format!(
+                "Signature detected in {} of {} files",
+                detected_signatures, scanned_files
+            )
+            .blue()
         );
     }
 
-    let subtitle_files_before_rename = subtitle_files.len();
+    if run_options.only_missing {
+        skip_subtitled_movie_files(&mut movie_files, &mut subtitle_files);
+    }
 
-    let mut renamed_subtitle_files = Vec::new();
+    #[cfg(feature = "zip")]
+    let zipped_pairs = if cli.zipped_subtitles {
+        let zipped_subtitle_files =
+            collect_zipped_subtitle_files(directory, &ScanOptions::from_cli(cli))?;
+        take_zipped_pairs(&mut movie_files, zipped_subtitle_files, rename_options)
+    } else {
+        ZippedPairs::default()
+    };
 
-    // keeping track of what subtitle file to remove from the vec after being renamed for efficiency
-    let mut subtitle_file_index_to_remove: Option<usize> = None;
+    let duplicate_signatures = find_duplicate_signatures(&movie_files);
+    for (signature, duplicate_movie_files) in &duplicate_signatures {
+        log::warn!(
+            "Movie files sharing the {} signature, only one of them will get a subtitle file: {}",
+            signature,
+            duplicate_movie_files
+                .iter()
+                .map(|movie_file| format!("'{}'", movie_file))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
+    if cli.strict && !duplicate_signatures.is_empty() {
+        bail!("Movie files sharing the same signature have been found");
+    }
+
+    check_file_counts(
+        movie_files.len(),
+        subtitle_files.len(),
+        run_options.count_mismatch,
+    )?;
 
-    movie_files.iter().for_each(|movie_file| {
-        subtitle_files
+    let planned_pairs = if cli.rename_subtitles_to_index {
+        plan_pairs_by_index(&movie_files, &subtitle_files)
+    } else if let Some(crc_list) = &cli.crc_list {
+        let crc_list = crc_list::read_crc_list(io::BufReader::new(fs::File::open(crc_list)?))?;
+        plan_pairs_by_crc(&movie_files, &subtitle_files, &crc_list)?
+    } else {
+        plan_pairs(&movie_files, &subtitle_files, rename_options)
+    };
+
+    if cli.match_only {
+        let matched_pairs: Vec<MatchedPair> = planned_pairs
             .iter()
-            .enumerate()
-            .any(|(index, subtitle_file)| {
-                if let Err(err) = subtitle_file.rename_using_movie_file(movie_file) {
-                    match err {
-                        SubtitleFileError::FileSystem(err) => {
-                            log::error!("{}", err);
-                            log::warn!("Skipping errored file: '{}'", subtitle_file);
-                        }
-                        SubtitleFileError::AlreadyRenamed => {
-                            log::warn!("Skipping already renamed file: '{}'", subtitle_file)
-                        }
-                        _ => {}
+            .map(|(movie_index, subtitle_index)| {
+                MatchedPair::new(&movie_files[*movie_index], &subtitle_files[*subtitle_index])
+            })
+            .collect();
+        #[cfg(feature = "zip")]
+        let matched_pairs: Vec<MatchedPair> = matched_pairs
+            .into_iter()
+            .chain(
+                zipped_pairs
+                    .pairs
+                    .iter()
+                    .map(|(movie_file, zipped_subtitle_file)| {
+                        MatchedPair::new(movie_file, zipped_subtitle_file)
+                    }),
+            )
+            .collect();
+
+        report::print_matched_pairs(&matched_pairs, cli.format);
+        return Ok(());
+    }
+
+    if cli.dry_run {
+        let records = dry_run_records(
+            &movie_files,
+            &subtitle_files,
+            &planned_pairs,
+            rename_options,
+        );
+        #[cfg(feature = "zip")]
+        let records = [records, zipped_pairs.dry_run_records(rename_options)].concat();
+        if let Some(report) = &cli.report {
+            write_report(report, &records)?;
+        }
+        if let Some(summary_json) = &cli.summary_json_to {
+            write_json_summary(summary_json, true, &records)?;
+        }
+        if print_focused_results(cli, true, &records)? {
+            return Ok(());
+        }
+
+        match cli.format {
+            OutputFormat::Human => {
+                for record in &records {
+                    if record.status == Some(RenameStatus::WouldRename) {
+                        println!(
+                            "{} Would rename subtitle file '{}' to '{}'",
+                            "->".yellow(),
+                            record.subtitle,
+                            record.new_name
+                        );
                     }
-                    false
-                } else {
+                }
+            }
+            OutputFormat::Json => println!(
+                "{}",
+                RunResults {
+                    dry_run: true,
+                    results: &records
+                }
+                .to_json()
+            ),
+        }
+        return Ok(());
+    }
+
+    if let Some(plan_file) = &cli.plan_file {
+        let planned_renames = planned_renames(
+            &movie_files,
+            &subtitle_files,
+            &planned_pairs,
+            rename_options,
+        );
+
+        let mut writer = io::BufWriter::new(fs::File::create(plan_file)?);
+        plan::write_plan(&mut writer, &planned_renames)?;
+        writer.flush()?;
+
+        println!(
+            "{} Written {} planned renames to '{}'",
+            "->".green(),
+            planned_renames.len(),
+            plan_file.display()
+        );
+        return Ok(());
+    }
+
+    let planned_rename_count = planned_pairs.len();
+    #[cfg(feature = "zip")]
+    let planned_rename_count = planned_rename_count + zipped_pairs.pairs.len();
+    if planned_rename_count > cli.confirm_threshold {
+        let question = format!(
+            "{} subtitle files are about to be renamed, do you want to continue?",
+            planned_rename_count
+        );
+        if !confirm(
+            &question,
+            run_options.assume_yes,
+            &mut io::stdin().lock(),
+            &mut io::stderr(),
+        )? {
+            bail!("Aborted, nothing has been renamed");
+        }
+    }
+
+    let mut records = Vec::new();
+    let mut movie_file_matched = vec![false; movie_files.len()];
+    let mut subtitle_file_renamed = vec![false; subtitle_files.len()];
+    // Skipped and failed subtitle files have their own records, they are not unmatched
+    let mut subtitle_file_handled = vec![false; subtitle_files.len()];
+
+    let mut rename_log = cli.rename_log.as_deref().map(open_rename_log).transpose()?;
+
+    for (movie_index, subtitle_index) in planned_pairs {
+        let movie_file = &movie_files[movie_index];
+        let subtitle_file = &subtitle_files[subtitle_index];
+
+        let new_path = subtitle_file.planned_path(movie_file, rename_options);
+        let record = |status| {
+            RenameRecord::new(Some(movie_file), subtitle_file, new_path.as_deref(), status)
+        };
+
+        let status = rename_planned_pair(movie_file, subtitle_file, rename_options);
+        match status {
+            RenameStatus::Renamed => {
+                if prints_progress(cli) {
                     println!("{} Renamed subtitle file '{}'", "->".green(), subtitle_file);
-                    subtitle_file_index_to_remove = Some(index);
-                    true
                 }
-            });
+                subtitle_file_renamed[subtitle_index] = true;
+                movie_file_matched[movie_index] = true;
+            }
+            _ => {
+                subtitle_file_handled[subtitle_index] = true;
+                movie_file_matched[movie_index] = true;
+            }
+        }
+
+        let record = record(status);
+        if let Some(rename_log) = &mut rename_log {
+            report::write_json_line(rename_log, &record)?;
+        }
+        records.push(record);
+    }
+
+    let mut renamed_subtitle_files = Vec::new();
+    let mut non_renamed_subtitle_files = Vec::new();
 
-        if let Some(index) = subtitle_file_index_to_remove {
-            let subtitle_file = subtitle_files.swap_remove(index);
-            if cli.summarize {
-                renamed_subtitle_files.push(subtitle_file);
+    #[cfg(feature = "zip")]
+    for (movie_file, zipped_subtitle_file) in &zipped_pairs.pairs {
+        let status = rename_zipped_pair(movie_file, zipped_subtitle_file, rename_options);
+        match status {
+            RenameStatus::Renamed => {
+                if prints_progress(cli) {
+                    println!(
+                        "{} Renamed zipped subtitle file '{}'",
+                        "->".green(),
+                        zipped_subtitle_file
+                    );
+                }
+                renamed_subtitle_files.push(zipped_subtitle_file.to_string());
             }
-            subtitle_file_index_to_remove = None;
+            _ => non_renamed_subtitle_files.push(zipped_subtitle_file.to_string()),
         }
-    });
+
+        let new_path = zipped_subtitle_file
+            .planned_path(movie_file, rename_options)
+            .map(|(new_archive_path, _)| new_archive_path);
+        let record = RenameRecord::new(
+            Some(movie_file),
+            zipped_subtitle_file,
+            new_path.as_deref(),
+            status,
+        );
+        if let Some(rename_log) = &mut rename_log {
+            report::write_json_line(rename_log, &record)?;
+        }
+        records.push(record);
+    }
+
+    for (movie_file, _) in movie_files
+        .iter()
+        .zip(&movie_file_matched)
+        .filter(|(_, matched)| !**matched)
+    {
+        records.push(RenameRecord::unmatched_movie(movie_file));
+    }
+
+    for (subtitle_index, subtitle_file) in subtitle_files.iter().enumerate() {
+        if subtitle_file_renamed[subtitle_index] {
+            renamed_subtitle_files.push(subtitle_file.to_string());
+            continue;
+        }
+
+        if !subtitle_file_handled[subtitle_index] {
+            records.push(RenameRecord::new(
+                None,
+                subtitle_file,
+                None,
+                RenameStatus::Unmatched,
+            ));
+        }
+        non_renamed_subtitle_files.push(subtitle_file.to_string());
+    }
+
+    #[cfg(feature = "zip")]
+    for zipped_subtitle_file in &zipped_pairs.unmatched {
+        records.push(RenameRecord::new(
+            None,
+            zipped_subtitle_file,
+            None,
+            RenameStatus::Unmatched,
+        ));
+        non_renamed_subtitle_files.push(zipped_subtitle_file.to_string());
+    }
+    let subtitle_files = non_renamed_subtitle_files;
+
+    let stats = RunStats::from_records(&records);
+
+    if let Some(report) = &cli.report {
+        write_report(report, &records)?;
+    }
+    if let Some(summary_json) = &cli.summary_json_to {
+        write_json_summary(summary_json, false, &records)?;
+    }
+    if print_focused_results(cli, false, &records)? {
+        return Ok(());
+    }
+
+    if cli.format == OutputFormat::Json {
+        println!(
+            "{}",
+            RunResults {
+                dry_run: false,
+                results: &records
+            }
+            .to_json()
+        );
+        if cli.stats {
+            stats.print(cli.format);
+        }
+        return Ok(());
+    }
 
     if cli.summarize {
         println!("\n-------------- SUMMARY --------------");
@@ -108,8 +957,8 @@ fn main() -> Result<()> {
         if renamed_subtitle_files.is_empty() {
             println!("Nothing.");
         } else {
-            for sub in renamed_subtitle_files {
-                println!("- {}", format!("{}", sub).green());
+            for sub in &renamed_subtitle_files {
+                println!("- {}", sub.green());
             }
         }
 
@@ -118,20 +967,2344 @@ fn main() -> Result<()> {
             println!("Nothing.");
         } else {
             for sub in &subtitle_files {
-                println!("- {}", format!("{}", sub).red());
+                println!("- {}", sub.red());
             }
         }
     }
 
+    if cli.group_by_show {
+        println!("\n-------------- SHOWS --------------");
+        report::write_groups_by_show(&mut io::stdout().lock(), &records)?;
+    }
+
     println!(
         "\n{}",
         format!(
             "Renamed subtitle files : {}, Non-renamed subtitle files: {}",
-            format!("{}", subtitle_files_before_rename - subtitle_files.len()).green(),
+            format!("{}", renamed_subtitle_files.len()).green(),
             format!("{}", subtitle_files.len()).red()
         )
         .blue()
     );
 
+    if cli.stats {
+        stats.print(cli.format);
+    }
+
+    Ok(())
+}
+
+/// Returns whether lines are printed as the run goes. Only the machine readable reports are
+/// printed in the json format, and only the final block when the output is focused on the
+/// summary or on the problems
+fn prints_progress(cli: &Cli) -> bool {
+    cli.format == OutputFormat::Human && !cli.summary_only && !cli.report_unmatched
+}
+
+/// Prints the summary of --summary-only, or the problems of --report-unmatched, in the output
+/// format once the run completes, returning false when neither has been asked for
+fn print_focused_results(cli: &Cli, dry_run: bool, records: &[RenameRecord]) -> Result<bool> {
+    print_focused_results_to(&mut io::stdout().lock(), cli, dry_run, records)
+}
+
+/// Writes what [`print_focused_results`] prints
+fn print_focused_results_to(
+    writer: &mut impl Write,
+    cli: &Cli,
+    dry_run: bool,
+    records: &[RenameRecord],
+) -> Result<bool> {
+    if cli.summary_only {
+        let summary = RunSummary::new(dry_run, records);
+        match cli.format {
+            OutputFormat::Human => report::write_summary(writer, &summary)?,
+            OutputFormat::Json => writeln!(writer, "{}", summary.to_json())?,
+        }
+        return Ok(true);
+    }
+
+    if cli.report_unmatched {
+        match cli.format {
+            OutputFormat::Human => report::write_problems(writer, records)?,
+            OutputFormat::Json => {
+                let problems: Vec<RenameRecord> = records
+                    .iter()
+                    .filter(|record| record.is_problem())
+                    .cloned()
+                    .collect();
+                writeln!(
+                    writer,
+                    "{}",
+                    RunResults {
+                        dry_run,
+                        results: &problems
+                    }
+                    .to_json()
+                )?
+            }
+        }
+        return Ok(true);
+    }
+
+    Ok(false)
+}
+
+/// Renames the subtitle file of a planned pair after its movie file, returning what happened
+/// to it. Errors, and even panics, only fail this pair so that the rest of the batch proceeds
+fn rename_planned_pair(
+    movie_file: &MovieFile,
+    subtitle_file: &SubtitleFile,
+    rename_options: &RenameOptions,
+) -> RenameStatus {
+    let rename = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+        if rename_options.copy_as.is_empty() {
+            subtitle_file.rename_using_movie_file_with_options(movie_file, rename_options)
+        } else {
+            subtitle_file
+                .copy_using_movie_file_with_options(movie_file, rename_options)
+                .map(|copy_paths| match copy_paths.into_iter().next() {
+                    Some(copy_path) => RenameOutcome::Moved(copy_path),
+                    None => RenameOutcome::AlreadyCorrect,
+                })
+        }
+    }));
+
+    rename_status(rename, subtitle_file)
+}
+
+/// Renames the zipped subtitle file after its paired movie file, the way
+/// [`rename_planned_pair`] renames plain subtitle files
+#[cfg(feature = "zip")]
+fn rename_zipped_pair(
+    movie_file: &MovieFile,
+    zipped_subtitle_file: &ZippedSubtitleFile,
+    rename_options: &RenameOptions,
+) -> RenameStatus {
+    let rename = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+        zipped_subtitle_file.rename_using_movie_file_with_options(movie_file, rename_options)
+    }));
+
+    rename_status(rename, zipped_subtitle_file)
+}
+
+/// Returns the status of the rename of a subtitle file, logging why it has been skipped
+fn rename_status(
+    rename: std::thread::Result<Result<RenameOutcome, SubtitleFileError>>,
+    subtitle_file: &impl std::fmt::Display,
+) -> RenameStatus {
+    match rename {
+        Ok(Ok(RenameOutcome::Moved(_))) => RenameStatus::Renamed,
+        Ok(Ok(RenameOutcome::AlreadyCorrect)) => {
+            log::warn!("Skipping already renamed file: '{}'", subtitle_file);
+            RenameStatus::Skipped
+        }
+        Ok(Err(SubtitleFileError::TargetExists(target))) => {
+            log::warn!(
+                "Skipping '{}' as '{}' already exists",
+                subtitle_file,
+                target.display()
+            );
+            RenameStatus::Skipped
+        }
+        Ok(Err(err)) => {
+            log::error!("{}", err);
+            log::warn!("Skipping errored file: '{}'", subtitle_file);
+            RenameStatus::Failed
+        }
+        Err(_) => {
+            log::error!("Renaming '{}' failed unexpectedly", subtitle_file);
+            log::warn!("Skipping errored file: '{}'", subtitle_file);
+            RenameStatus::Failed
+        }
+    }
+}
+
+/// Writes the records as a CSV report to the file
+fn write_report(report: &path::Path, records: &[RenameRecord]) -> Result<()> {
+    let mut writer = io::BufWriter::new(fs::File::create(report)?);
+    report::write_csv_report(&mut writer, records)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Opens the rename log for appending, creating it when it doesn't exist. Every line is
+/// written out as soon as it is complete so that the log can be tailed
+fn open_rename_log(rename_log: &path::Path) -> Result<io::LineWriter<fs::File>> {
+    let file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(rename_log)?;
+    Ok(io::LineWriter::new(file))
+}
+
+/// Writes the records as the JSON results of the run to the file
+fn write_json_summary(
+    summary_json: &path::Path,
+    dry_run: bool,
+    records: &[RenameRecord],
+) -> Result<()> {
+    let run_results = RunResults {
+        dry_run,
+        results: records,
+    };
+    fs::write(summary_json, run_results.to_json() + "\n")?;
     Ok(())
 }
+
+/// Returns the records of what renaming the planned pairs would do, without renaming anything
+fn dry_run_records(
+    movie_files: &[MovieFile],
+    subtitle_files: &[SubtitleFile],
+    planned_pairs: &[(usize, usize)],
+    rename_options: &RenameOptions,
+) -> Vec<RenameRecord> {
+    let mut records = Vec::new();
+    let mut movie_file_paired = vec![false; movie_files.len()];
+    let mut subtitle_file_paired = vec![false; subtitle_files.len()];
+
+    for (movie_index, subtitle_index) in planned_pairs {
+        let movie_file = &movie_files[*movie_index];
+        let subtitle_file = &subtitle_files[*subtitle_index];
+        let new_path = subtitle_file.planned_path(movie_file, rename_options);
+
+        let status = if new_path.as_deref().and_then(path::Path::file_name)
+            == subtitle_file.path().file_name()
+        {
+            RenameStatus::Skipped
+        } else {
+            RenameStatus::WouldRename
+        };
+
+        records.push(RenameRecord::new(
+            Some(movie_file),
+            subtitle_file,
+            new_path.as_deref(),
+            status,
+        ));
+        movie_file_paired[*movie_index] = true;
+        subtitle_file_paired[*subtitle_index] = true;
+    }
+
+    for (movie_file, _) in movie_files
+        .iter()
+        .zip(&movie_file_paired)
+        .filter(|(_, paired)| !**paired)
+    {
+        records.push(RenameRecord::unmatched_movie(movie_file));
+    }
+    for (subtitle_file, _) in subtitle_files
+        .iter()
+        .zip(&subtitle_file_paired)
+        .filter(|(_, paired)| !**paired)
+    {
+        records.push(RenameRecord::new(
+            None,
+            subtitle_file,
+            None,
+            RenameStatus::Unmatched,
+        ));
+    }
+
+    records
+}
+
+/// Returns the renames of the planned pairs, leaving out the subtitle files that have already
+/// been renamed
+fn planned_renames(
+    movie_files: &[MovieFile],
+    subtitle_files: &[SubtitleFile],
+    planned_pairs: &[(usize, usize)],
+    rename_options: &RenameOptions,
+) -> Vec<plan::PlannedRename> {
+    planned_pairs
+        .iter()
+        .filter_map(|(movie_index, subtitle_index)| {
+            let subtitle_file = &subtitle_files[*subtitle_index];
+            let new = subtitle_file.planned_path(&movie_files[*movie_index], rename_options)?;
+
+            (new != subtitle_file.path()).then(|| plan::PlannedRename {
+                old: subtitle_file.path().to_path_buf(),
+                new,
+            })
+        })
+        .collect()
+}
+
+/// Carries out the renames of the plan file, returning the number of renamed subtitle files.
+/// Existing files are never overwritten, renames that fail are skipped with an error message
+fn apply_plan(plan_file: &path::Path, rename_options: &RenameOptions) -> Result<usize> {
+    let planned_renames = plan::read_plan(io::BufReader::new(fs::File::open(plan_file)?))?;
+    let mut renamed = 0;
+
+    for planned_rename in planned_renames {
+        let subtitle_file = SubtitleFile::try_from(planned_rename.old.clone());
+        #[cfg(feature = "gzip")]
+        let subtitle_file =
+            subtitle_file.or_else(|_| SubtitleFile::from_gzip(planned_rename.old.clone()));
+        let subtitle_file = match subtitle_file {
+            Ok(subtitle_file) => subtitle_file,
+            Err(err) => {
+                log::error!("{}", err);
+                continue;
+            }
+        };
+
+        match subtitle_file.rename_to_path_with_options(&planned_rename.new, rename_options) {
+            Ok(_) => {
+                println!("{} Renamed subtitle file '{}'", "->".green(), subtitle_file);
+                renamed += 1;
+            }
+            Err(err) => {
+                log::error!("{}", err);
+                log::warn!("Skipping errored file: '{}'", subtitle_file);
+            }
+        }
+    }
+
+    Ok(renamed)
+}
+
+/// Checks that there are as many movie files as subtitle files, a difference is an error unless
+/// the count mismatch is to be warned about or ignored
+fn check_file_counts(
+    movie_file_count: usize,
+    subtitle_file_count: usize,
+    count_mismatch: CountMismatch,
+) -> Result<()> {
+    if count_mismatch == CountMismatch::Ignore || movie_file_count == subtitle_file_count {
+        return Ok(());
+    }
+
+    let mismatch = format!(
+        "Total movie files are not the same as total subtitle files. Movies: {}, Subtitles: {}",
+        movie_file_count, subtitle_file_count,
+    );
+    if count_mismatch == CountMismatch::Warn {
+        log::warn!("{}", mismatch);
+        return Ok(());
+    }
+    bail!(mismatch)
+}
+
+/// Leaves out the movie files already having a subtitle file with their stem beside them,
+/// alongside those subtitle files
+fn skip_subtitled_movie_files(
+    movie_files: &mut Vec<MovieFile>,
+    subtitle_files: &mut Vec<SubtitleFile>,
+) {
+    let existing_subtitle_file_paths: HashSet<path::PathBuf> = movie_files
+        .iter()
+        .map(|movie_file| movie_file.path().with_extension(SUBTITLE_EXTENSION))
+        .filter(|subtitle_file_path| subtitle_file_path.exists())
+        .collect();
+
+    movie_files.retain(|movie_file| {
+        let subtitled = existing_subtitle_file_paths
+            .contains(&movie_file.path().with_extension(SUBTITLE_EXTENSION));
+        if subtitled {
+            log::debug!("Skipping already subtitled movie file: '{}'", movie_file);
+        }
+        !subtitled
+    });
+    subtitle_files
+        .retain(|subtitle_file| !existing_subtitle_file_paths.contains(subtitle_file.path()));
+}
+
+/// Returns the signatures shared by more than one movie file, like when there are multiple
+/// quality versions of the same episode, alongside the movie files sharing them
+fn find_duplicate_signatures(movie_files: &[MovieFile]) -> Vec<(Signature, Vec<&MovieFile>)> {
+    let mut movie_files_by_signature: HashMap<Signature, Vec<&MovieFile>> = HashMap::new();
+
+    for movie_file in movie_files {
+        if let Some(signature) = movie_file.signature() {
+            movie_files_by_signature
+                .entry(signature)
+                .or_default()
+                .push(movie_file);
+        }
+    }
+
+    let mut duplicate_signatures: Vec<_> = movie_files_by_signature
+        .into_iter()
+        .filter(|(_, movie_files)| movie_files.len() > 1)
+        .collect();
+    duplicate_signatures.sort_by_key(|(signature, _)| *signature);
+
+    duplicate_signatures
+}
+
+/// Returns how many of the movie and subtitle files have a signature detected the way they are
+/// matched with the rename options, see [`MovieFile::match_key`], along with the number of
+/// files, telling how well the files are named
+fn count_detected_signatures(
+    movie_files: &[MovieFile],
+    subtitle_files: &[SubtitleFile],
+    rename_options: &RenameOptions,
+) -> (usize, usize) {
+    let detected_signatures = movie_files
+        .iter()
+        .filter(|movie_file| movie_file.match_key(rename_options).is_some())
+        .count()
+        + subtitle_files
+            .iter()
+            .filter(|subtitle_file| subtitle_file.match_key(rename_options).is_some())
+            .count();
+
+    (
+        detected_signatures,
+        movie_files.len() + subtitle_files.len(),
+    )
+}
+
+/// Pairs the movie files with the subtitle files matching them, returning the indices of the
+/// paired movie and subtitle files ordered by movie file.
+/// When a subtitle file matches several movie files, like when different shows share the same
+/// signatures, the pairs with the most similar names are preferred over the first encountered
+/// ones, and ties go to the movie and subtitle files coming first, so that the pairing doesn't
+/// depend on the order of the files in any other way
+fn plan_pairs(
+    movie_files: &[MovieFile],
+    subtitle_files: &[SubtitleFile],
+    rename_options: &RenameOptions,
+) -> Vec<(usize, usize)> {
+    plan_best_pairs(
+        movie_files,
+        subtitle_files,
+        SubtitleFile::path,
+        |movie_file, subtitle_file| {
+            subtitle_file
+                .planned_path(movie_file, rename_options)
+                .is_some()
+        },
+    )
+}
+
+/// Pairs the movie files with the subtitle files of any kind the way [`plan_pairs`] does, the
+/// subtitle files matching the movie files being told by `matches`
+fn plan_best_pairs<T>(
+    movie_files: &[MovieFile],
+    subtitle_files: &[T],
+    path: impl Fn(&T) -> &path::Path,
+    matches: impl Fn(&MovieFile, &T) -> bool,
+) -> Vec<(usize, usize)> {
+    let mut candidate_pairs = Vec::new();
+    for (movie_index, movie_file) in movie_files.iter().enumerate() {
+        for (subtitle_index, subtitle_file) in subtitle_files.iter().enumerate() {
+            if matches(movie_file, subtitle_file) {
+                let similarity = name_similarity(movie_file.path(), path(subtitle_file));
+                candidate_pairs.push((similarity, movie_index, subtitle_index));
+            }
+        }
+    }
+    candidate_pairs.sort_by_key(|(similarity, movie_index, subtitle_index)| {
+        (
+            std::cmp::Reverse(*similarity),
+            *movie_index,
+            *subtitle_index,
+        )
+    });
+
+    let mut planned_pairs = Vec::new();
+    let mut movie_file_paired = vec![false; movie_files.len()];
+    let mut subtitle_file_paired = vec![false; subtitle_files.len()];
+
+    for (_, movie_index, subtitle_index) in candidate_pairs {
+        if !movie_file_paired[movie_index] && !subtitle_file_paired[subtitle_index] {
+            movie_file_paired[movie_index] = true;
+            subtitle_file_paired[subtitle_index] = true;
+            planned_pairs.push((movie_index, subtitle_index));
+        }
+    }
+
+    planned_pairs.sort_unstable();
+    planned_pairs
+}
+
+/// Pairs the movie files with the subtitle files by their position in the natural order of
+/// their paths, the first movie file with the first subtitle file and so on, ignoring
+/// signatures. Returns the indices of the paired movie and subtitle files ordered by movie file,
+/// the files left over when there are more files of one kind stay unpaired
+fn plan_pairs_by_index(
+    movie_files: &[MovieFile],
+    subtitle_files: &[SubtitleFile],
+) -> Vec<(usize, usize)> {
+    let mut movie_indices: Vec<usize> = (0..movie_files.len()).collect();
+    movie_indices.sort_by(|first, second| {
+        natural_cmp(movie_files[*first].path(), movie_files[*second].path())
+    });
+    let mut subtitle_indices: Vec<usize> = (0..subtitle_files.len()).collect();
+    subtitle_indices.sort_by(|first, second| {
+        natural_cmp(
+            subtitle_files[*first].path(),
+            subtitle_files[*second].path(),
+        )
+    });
+
+    let mut planned_pairs: Vec<_> = movie_indices.into_iter().zip(subtitle_indices).collect();
+    planned_pairs.sort_unstable();
+    planned_pairs
+}
+
+/// Compares the paths the way people order numbered files, the runs of digits being compared by
+/// their values, so that "Show 2.mkv" comes before "Show 10.mkv" and "Show 02.mkv" sits next
+/// to "Show 2.mkv"
+fn natural_cmp(first: &path::Path, second: &path::Path) -> std::cmp::Ordering {
+    let first = first.to_string_lossy();
+    let second = second.to_string_lossy();
+    let mut first_chars = first.chars().peekable();
+    let mut second_chars = second.chars().peekable();
+
+    let take_digits = |chars: &mut std::iter::Peekable<std::str::Chars>| {
+        let mut digits = String::new();
+        while let Some(x) = chars.next_if(char::is_ascii_digit) {
+            digits.push(x);
+        }
+        digits
+    };
+
+    loop {
+        match (first_chars.peek(), second_chars.peek()) {
+            (Some(first_char), Some(second_char))
+                if first_char.is_ascii_digit() && second_char.is_ascii_digit() =>
+            {
+                let first_digits = take_digits(&mut first_chars);
+                let second_digits = take_digits(&mut second_chars);
+                let first_value = first_digits.trim_start_matches('0');
+                let second_value = second_digits.trim_start_matches('0');
+
+                let ordering = first_value
+                    .len()
+                    .cmp(&second_value.len())
+                    .then_with(|| first_value.cmp(second_value))
+                    // Equal values, like "02" and "2", are told apart by their padding
+                    .then_with(|| first_digits.len().cmp(&second_digits.len()));
+                if ordering.is_ne() {
+                    return ordering;
+                }
+            }
+            (Some(first_char), Some(second_char)) => {
+                let ordering = first_char.cmp(second_char);
+                if ordering.is_ne() {
+                    return ordering;
+                }
+                first_chars.next();
+                second_chars.next();
+            }
+            (first_char, second_char) => return first_char.is_some().cmp(&second_char.is_some()),
+        }
+    }
+}
+
+/// Pairs each movie file having its CRC32 in the hash list with the first subtitle file, in the
+/// order of their paths, numbered with the episode the hash is listed with. Subtitle files are
+/// numbered by their absolute episode, like "Show - 27.srt", or by the episode of their
+/// signature. Returns the indices of the paired movie and subtitle files ordered by movie file
+///
+/// # Errors
+/// Returns an error when reading a movie file fails
+fn plan_pairs_by_crc(
+    movie_files: &[MovieFile],
+    subtitle_files: &[SubtitleFile],
+    crc_list: &HashMap<u32, u32>,
+) -> Result<Vec<(usize, usize)>> {
+    let mut subtitle_indices: Vec<usize> = (0..subtitle_files.len()).collect();
+    subtitle_indices.sort_by_key(|subtitle_index| subtitle_files[*subtitle_index].path());
+    let subtitle_episode = |subtitle_file: &SubtitleFile| {
+        subtitle_file
+            .absolute_episode()
+            .or_else(|| subtitle_file.signature().map(|signature| signature.episode))
+    };
+
+    let mut planned_pairs = Vec::new();
+    let mut subtitle_file_paired = vec![false; subtitle_files.len()];
+    for (movie_index, movie_file) in movie_files.iter().enumerate() {
+        log::info!("Computing the CRC32 of '{}'", movie_file);
+        let crc = crc_list::file_crc32(movie_file.path())?;
+        let Some(episode) = crc_list.get(&crc) else {
+            log::warn!("The CRC32 {:08X} of '{}' is not listed", crc, movie_file);
+            continue;
+        };
+
+        let subtitle_index = subtitle_indices.iter().copied().find(|subtitle_index| {
+            !subtitle_file_paired[*subtitle_index]
+                && subtitle_episode(&subtitle_files[*subtitle_index]) == Some(*episode)
+        });
+        if let Some(subtitle_index) = subtitle_index {
+            subtitle_file_paired[subtitle_index] = true;
+            planned_pairs.push((movie_index, subtitle_index));
+        }
+    }
+
+    Ok(planned_pairs)
+}
+
+/// Returns how similar the names of the files are, that is the number of distinct words, case
+/// ignored, found in both file stems
+fn name_similarity(first_path: &path::Path, second_path: &path::Path) -> usize {
+    let words = |file_path: &path::Path| {
+        file_path
+            .file_stem()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .split(|x: char| !x.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .map(str::to_lowercase)
+            .collect::<std::collections::HashSet<_>>()
+    };
+
+    words(first_path).intersection(&words(second_path)).count()
+}
+
+/// Asks the question on the output and reads the answer from the input, returning whether
+/// the answer is yes. The question is not asked at all when yes is assumed
+fn confirm(
+    question: &str,
+    assume_yes: bool,
+    input: &mut impl io::BufRead,
+    output: &mut impl io::Write,
+) -> io::Result<bool> {
+    if assume_yes {
+        return Ok(true);
+    }
+
+    write!(output, "{} [y/N] ", question)?;
+    output.flush()?;
+
+    let mut answer = String::new();
+    input.read_line(&mut answer)?;
+
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+/// Returns how deep to walk the directory for the recursive options, counting the directory
+/// itself as the depth 0 and its entries as the depth 1
+fn walk_depth(recursive: bool, max_depth: Option<usize>) -> usize {
+    match max_depth {
+        Some(max_depth) => max_depth.saturating_add(1),
+        None if recursive => usize::MAX,
+        None => 1,
+    }
+}
+
+/// Options changing which files are found when scanning a directory
+#[derive(Default)]
+struct ScanOptions<'a> {
+    /// How deep to walk the directory, see [`walk_depth`]
+    depth: usize,
+    /// Only find the files modified within this duration
+    since: Option<Duration>,
+    /// Extra movie extensions to find along with the default ones
+    extra_movie_extensions: Option<&'a Vec<String>>,
+    /// Movie extensions to leave out
+    excluded_movie_extensions: &'a [String],
+    /// Whether to find the .txt files having SRT content
+    sniff_txt: bool,
+    /// Whether to only find files with exactly the expected extensions, the case of the
+    /// extensions is ignored otherwise
+    strict_extension_case: bool,
+}
+
+impl<'a> ScanOptions<'a> {
+    /// Returns the scan options set on the command line
+    fn from_cli(cli: &'a Cli) -> Self {
+        Self {
+            depth: walk_depth(cli.recursive, cli.max_depth),
+            since: cli.since,
+            extra_movie_extensions: cli.extra_movie_extensions.as_ref(),
+            excluded_movie_extensions: &cli.exclude_ext,
+            sniff_txt: cli.sniff_txt,
+            strict_extension_case: !cli.ignore_extension_case,
+        }
+    }
+}
+
+/// The order in which the collected files are processed
+#[derive(Debug, Default, Clone, Copy, PartialEq, clap::ValueEnum)]
+enum SortKey {
+    /// In the order of their paths
+    #[default]
+    Name,
+    /// The most recently modified files first
+    Mtime,
+    /// The largest files first
+    Size,
+}
+
+/// Sorts the files by the key, keeping the order of the paths between files having the same
+/// key. Files whose metadata can't be read come last
+fn sort_files<T>(files: &mut [T], path: impl Fn(&T) -> &path::Path, sort: SortKey) {
+    files.sort_by(|first, second| path(first).cmp(path(second)));
+
+    match sort {
+        SortKey::Name => {}
+        SortKey::Mtime => files.sort_by_cached_key(|file| {
+            std::cmp::Reverse(
+                fs::metadata(path(file))
+                    .and_then(|metadata| metadata.modified())
+                    .ok(),
+            )
+        }),
+        SortKey::Size => files.sort_by_cached_key(|file| {
+            std::cmp::Reverse(fs::metadata(path(file)).map(|metadata| metadata.len()).ok())
+        }),
+    }
+}
+
+/// A movie file or a subtitle file found when scanning a directory
+pub enum ScannedFile {
+    Movie(MovieFile),
+    Subtitle(SubtitleFile),
+}
+
+/// Collects the movie and subtitle files found in the directory and its subdirectories
+fn collect_files(
+    directory: &path::Path,
+    scan_options: &ScanOptions,
+) -> Result<(Vec<MovieFile>, Vec<SubtitleFile>)> {
+    let mut movie_files = Vec::new();
+    let mut subtitle_files = Vec::new();
+
+    scan_files(directory, scan_options, |scanned_file| match scanned_file {
+        ScannedFile::Movie(movie_file) => movie_files.push(movie_file),
+        ScannedFile::Subtitle(subtitle_file) => subtitle_files.push(subtitle_file),
+    })?;
+
+    Ok((movie_files, subtitle_files))
+}
+
+/// Scans the directory and its subdirectories, down to the depth of the scan options, handing
+/// every movie and subtitle file found to the given function as soon as it is found, in the
+/// order of the file names
+fn scan_files(
+    directory: &path::Path,
+    scan_options: &ScanOptions,
+    mut on_scanned_file: impl FnMut(ScannedFile),
+) -> Result<()> {
+    let ignore_patterns = read_ignore_file(directory)?;
+
+    let mut dir_entries = walkdir::WalkDir::new(directory)
+        .min_depth(1)
+        .max_depth(scan_options.depth)
+        .sort_by_file_name()
+        .into_iter();
+
+    while let Some(dir_entry) = dir_entries.next() {
+        let dir_entry = match dir_entry {
+            Ok(dir_entry) => dir_entry,
+            // Not being able to read the directory itself is fatal
+            Err(err) if err.depth() == 0 => return Err(err.into()),
+            Err(err) => {
+                log::error!("Error reading a directory entry: {}", err);
+                continue;
+            }
+        };
+
+        let is_dir = dir_entry.file_type().is_dir();
+        if ignore_patterns
+            .matched(dir_entry.path(), is_dir)
+            .is_ignore()
+        {
+            log::debug!("Ignoring '{}'", dir_entry.path().display());
+            if is_dir {
+                dir_entries.skip_current_dir();
+            }
+            continue;
+        }
+
+        if is_per_movie_subtitles_directory(dir_entry.path()) {
+            collect_per_movie_subtitle_files(dir_entry.path(), scan_options, &ignore_patterns)
+                .into_iter()
+                .for_each(|subtitle_file| on_scanned_file(ScannedFile::Subtitle(subtitle_file)));
+            dir_entries.skip_current_dir();
+            continue;
+        }
+
+        if is_dir {
+            continue;
+        }
+
+        if let Some(scanned_file) = scan_file(dir_entry.path(), scan_options) {
+            on_scanned_file(scanned_file);
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the movie file or the subtitle file at the path, None when the file is neither or
+/// is left out by the scan options
+fn scan_file(file_path: &path::Path, scan_options: &ScanOptions) -> Option<ScannedFile> {
+    if let Some(since) = scan_options.since {
+        if !modified_within(file_path, since) {
+            return None;
+        }
+    }
+
+    if let Some(movie_file) = MovieFile::new_with_extension_case(
+        file_path.into(),
+        scan_options.extra_movie_extensions,
+        !scan_options.strict_extension_case,
+    ) {
+        if is_excluded_movie_file(&movie_file, scan_options.excluded_movie_extensions) {
+            log::debug!("Leaving out excluded movie file: '{}'", movie_file);
+            return None;
+        }
+        return Some(ScannedFile::Movie(movie_file));
+    };
+
+    if let Ok(subtitle_file) = SubtitleFile::try_from_with_extension_case(
+        file_path.into(),
+        !scan_options.strict_extension_case,
+    ) {
+        return Some(ScannedFile::Subtitle(subtitle_file));
+    };
+
+    let is_txt_file = file_path
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case(TXT_FILE_EXTENSION));
+    if scan_options.sniff_txt && is_txt_file {
+        return SubtitleFile::from_srt_content(file_path.into())
+            .ok()
+            .map(ScannedFile::Subtitle);
+    }
+
+    #[cfg(feature = "gzip")]
+    match SubtitleFile::from_gzip(file_path.into()) {
+        Ok(subtitle_file) => return Some(ScannedFile::Subtitle(subtitle_file)),
+        Err(SubtitleFileError::InvalidSubtileFileName) => {}
+        Err(err) => log::warn!(
+            "Skipping compressed subtitle file '{}': {}",
+            file_path.display(),
+            err
+        ),
+    }
+
+    None
+}
+
+/// Collects the zip archives holding a single subtitle file found in the directory and its
+/// subdirectories, down to the depth of the scan options
+#[cfg(feature = "zip")]
+fn collect_zipped_subtitle_files(
+    directory: &path::Path,
+    scan_options: &ScanOptions,
+) -> Result<Vec<ZippedSubtitleFile>> {
+    let ignore_patterns = read_ignore_file(directory)?;
+    let mut zipped_subtitle_files = Vec::new();
+
+    for dir_entry in walkdir::WalkDir::new(directory)
+        .min_depth(1)
+        .max_depth(scan_options.depth)
+        .sort_by_file_name()
+    {
+        let dir_entry = dir_entry?;
+        if !dir_entry.file_type().is_file()
+            || ignore_patterns
+                .matched_path_or_any_parents(dir_entry.path(), false)
+                .is_ignore()
+        {
+            continue;
+        }
+
+        match ZippedSubtitleFile::open(dir_entry.path().into()) {
+            Ok(zipped_subtitle_file) => zipped_subtitle_files.push(zipped_subtitle_file),
+            Err(SubtitleFileError::InvalidSubtileFileName) => {}
+            Err(err) => log::warn!(
+                "Skipping zip archive '{}': {}",
+                dir_entry.path().display(),
+                err
+            ),
+        }
+    }
+
+    Ok(zipped_subtitle_files)
+}
+
+/// The zipped subtitle files of a run, split between those paired with movie files and the
+/// unmatched ones
+#[cfg(feature = "zip")]
+#[derive(Default)]
+struct ZippedPairs {
+    /// The zipped subtitle files along with the movie files they are renamed after
+    pairs: Vec<(MovieFile, ZippedSubtitleFile)>,
+    /// The zipped subtitle files matching no movie file
+    unmatched: Vec<ZippedSubtitleFile>,
+}
+
+#[cfg(feature = "zip")]
+impl ZippedPairs {
+    /// Returns the records of what renaming the zipped subtitle files would do, the way
+    /// [`dry_run_records`] does for plain subtitle files. Unmatched movie files are left to the
+    /// records of the plain subtitle files
+    fn dry_run_records(&self, rename_options: &RenameOptions) -> Vec<RenameRecord> {
+        let mut records = Vec::new();
+
+        for (movie_file, zipped_subtitle_file) in &self.pairs {
+            let planned_path = zipped_subtitle_file.planned_path(movie_file, rename_options);
+            let status = match &planned_path {
+                Some((new_archive_path, new_entry_name))
+                    if new_archive_path == zipped_subtitle_file.path()
+                        && new_entry_name == zipped_subtitle_file.entry_name() =>
+                {
+                    RenameStatus::Skipped
+                }
+                _ => RenameStatus::WouldRename,
+            };
+
+            records.push(RenameRecord::new(
+                Some(movie_file),
+                zipped_subtitle_file,
+                planned_path
+                    .as_ref()
+                    .map(|(new_archive_path, _)| new_archive_path.as_path()),
+                status,
+            ));
+        }
+        for zipped_subtitle_file in &self.unmatched {
+            records.push(RenameRecord::new(
+                None,
+                zipped_subtitle_file,
+                None,
+                RenameStatus::Unmatched,
+            ));
+        }
+
+        records
+    }
+}
+
+/// Pairs the zipped subtitle files with the movie files the way [`plan_pairs`] pairs plain
+/// subtitle files. The paired movie files are taken out of the movie files, as they got their
+/// subtitle files
+#[cfg(feature = "zip")]
+fn take_zipped_pairs(
+    movie_files: &mut Vec<MovieFile>,
+    zipped_subtitle_files: Vec<ZippedSubtitleFile>,
+    rename_options: &RenameOptions,
+) -> ZippedPairs {
+    let planned_pairs = plan_best_pairs(
+        movie_files,
+        &zipped_subtitle_files,
+        ZippedSubtitleFile::path,
+        |movie_file, zipped_subtitle_file| {
+            zipped_subtitle_file
+                .planned_path(movie_file, rename_options)
+                .is_some()
+        },
+    );
+
+    let mut movie_files_left: Vec<Option<MovieFile>> =
+        std::mem::take(movie_files).into_iter().map(Some).collect();
+    let mut zipped_subtitle_files_left: Vec<Option<ZippedSubtitleFile>> =
+        zipped_subtitle_files.into_iter().map(Some).collect();
+    let pairs = planned_pairs
+        .into_iter()
+        .map(|(movie_index, subtitle_index)| {
+            (
+                movie_files_left[movie_index]
+                    .take()
+                    .expect("each movie file is paired once"),
+                zipped_subtitle_files_left[subtitle_index]
+                    .take()
+                    .expect("each zipped subtitle file is paired once"),
+            )
+        })
+        .collect();
+
+    *movie_files = movie_files_left.into_iter().flatten().collect();
+    ZippedPairs {
+        pairs,
+        unmatched: zipped_subtitle_files_left.into_iter().flatten().collect(),
+    }
+}
+
+/// Returns whether the path is a "Subs" directory, which some releases use to keep the
+/// subtitle files of each movie in a "Subs/<movie-stem>/" folder
+fn is_per_movie_subtitles_directory(path: &path::Path) -> bool {
+    path.is_dir()
+        && path
+            .file_name()
+            .is_some_and(|name| name.eq_ignore_ascii_case(PER_MOVIE_SUBTITLES_DIRECTORY))
+}
+
+/// Collects the subtitle files found in the movie folders of a "Subs" directory, along with the
+/// ones kept directly in it, the way the scan options find them. The folders and files matching
+/// the ignore patterns are left out
+fn collect_per_movie_subtitle_files(
+    subs_directory: &path::Path,
+    scan_options: &ScanOptions,
+    ignore_patterns: &ignore::gitignore::Gitignore,
+) -> Vec<SubtitleFile> {
+    let mut subtitle_files = Vec::new();
+
+    let dir_entries = match fs::read_dir(subs_directory) {
+        Ok(dir_entries) => dir_entries,
+        Err(err) => {
+            log::error!("Error reading '{}': {}", subs_directory.display(), err);
+            return subtitle_files;
+        }
+    };
+
+    let is_ignored = |path: &path::Path, is_dir: bool| {
+        let ignored = ignore_patterns.matched(path, is_dir).is_ignore();
+        if ignored {
+            log::debug!("Ignoring '{}'", path.display());
+        }
+        ignored
+    };
+
+    let mut scan_subtitle_file = |file_path: &path::Path| {
+        if is_ignored(file_path, false) {
+            return;
+        }
+        if let Some(ScannedFile::Subtitle(subtitle_file)) = scan_file(file_path, scan_options) {
+            subtitle_files.push(subtitle_file);
+        }
+    };
+
+    for dir_entry in dir_entries.flatten() {
+        let entry_path = dir_entry.path();
+        if !entry_path.is_dir() {
+            // Subtitle files kept flat in the "Subs" directory, like "Subs/show s01e02.srt"
+            scan_subtitle_file(&entry_path);
+            continue;
+        }
+
+        if is_ignored(&entry_path, true) {
+            continue;
+        }
+        let Ok(movie_dir_entries) = fs::read_dir(&entry_path) else {
+            continue;
+        };
+        for movie_dir_entry in movie_dir_entries.flatten() {
+            scan_subtitle_file(&movie_dir_entry.path());
+        }
+    }
+
+    subtitle_files
+}
+
+/// Parses a duration made of a number and a unit, that is s, m, h, d or w for seconds,
+/// minutes, hours, days and weeks respectively
+fn parse_duration(duration: &str) -> Result<Duration, String> {
+    let unit_index = duration
+        .find(|x: char| !x.is_ascii_digit())
+        .ok_or_else(|| format!("'{}' has no unit, expected one of s, m, h, d, w", duration))?;
+    let (value, unit) = duration.split_at(unit_index);
+
+    let value: u64 = value
+        .parse()
+        .map_err(|_| format!("'{}' does not start with a valid number", duration))?;
+
+    let seconds_per_unit = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 60 * 60 * 24,
+        "w" => 60 * 60 * 24 * 7,
+        _ => {
+            return Err(format!(
+                "unknown unit '{}', expected one of s, m, h, d, w",
+                unit
+            ))
+        }
+    };
+
+    Ok(Duration::from_secs(value.saturating_mul(seconds_per_unit)))
+}
+
+/// Parses a regex having the "season" and "episode" named groups
+fn parse_pattern(pattern: &str) -> Result<Regex, String> {
+    let pattern = Regex::new(pattern).map_err(|err| err.to_string())?;
+
+    for group in ["season", "episode"] {
+        if !pattern.capture_names().flatten().any(|name| name == group) {
+            return Err(format!("the pattern has no '{}' named group", group));
+        }
+    }
+
+    Ok(pattern)
+}
+
+/// Parses a signature like "S03E04"
+fn parse_signature(signature: &str) -> Result<Signature, String> {
+    Signature::parse(signature)
+        .ok_or_else(|| format!("'{}' is not a signature like S03E04", signature))
+}
+
+/// Returns how the signatures of the file names are read with the options of the command line,
+/// one rule per line
+fn describe_matching(cli: &Cli) -> String {
+    if let Some(pattern) = &cli.pattern {
+        return format!("Custom pattern: {}\n", pattern.as_str());
+    }
+
+    let mut rules = vec![
+        "Built-in scanner, reading the signatures written as:".to_string(),
+        "- S01E02, S01 E02 or s01.e02, a letter like the \"a\" of S01E02a marking split episodes"
+            .to_string(),
+        "- Season 1 Episode 2, or Series 1 Episode 2".to_string(),
+        "- 1x02, standing on its own so that resolutions like 1920x1080 are left out".to_string(),
+        "- the season of a parent directory, like \"Season 1/Episode 2.srt\"".to_string(),
+    ];
+    if cli.roman_numeral_seasons {
+        rules.push("- Season IV or S.IV, seasons in Roman numerals".to_string());
+    }
+    if cli.volume_seasons {
+        rules.push("- Vol.1 or Volume 1 as seasons".to_string());
+    }
+    for season_marker in &cli.season_marker {
+        rules.push(format!("- {}01 as seasons", season_marker));
+    }
+    for episode_marker in &cli.episode_marker {
+        rules.push(format!("- {}02 as episodes", episode_marker));
+    }
+    if cli.numeric_code {
+        rules.push("- 0102 as S01E02, when names have no other signature".to_string());
+    }
+    if cli.hash_episodes {
+        rules.push("- #102 as absolute episode numbers".to_string());
+    }
+    if let Some(default_season) = cli.default_season {
+        rules.push(format!(
+            "- E02 as S{:02}E02, when names only have an episode",
+            default_season
+        ));
+    }
+    if cli.episode_map.is_some() {
+        rules.push("- absolute episode numbers mapped by the episode map".to_string());
+    }
+    if cli.episode_offset != 0 {
+        rules.push(format!(
+            "The episodes of the subtitle files are offset by {}",
+            cli.episode_offset
+        ));
+    }
+
+    rules.join("\n") + "\n"
+}
+
+/// Returns whether the extension of the movie file is one of the excluded extensions
+fn is_excluded_movie_file(movie_file: &MovieFile, excluded_extensions: &[String]) -> bool {
+    movie_file.path().extension().is_some_and(|extension| {
+        excluded_extensions.iter().any(|excluded_extension| {
+            extension.eq_ignore_ascii_case(excluded_extension.trim_start_matches('.'))
+        })
+    })
+}
+
+/// Reads the patterns of the ignore file of the directory, if it has one
+fn read_ignore_file(directory: &path::Path) -> Result<ignore::gitignore::Gitignore> {
+    let ignore_file = directory.join(IGNORE_FILE_NAME);
+    if !ignore_file.exists() {
+        return Ok(ignore::gitignore::Gitignore::empty());
+    }
+
+    let mut builder = ignore::gitignore::GitignoreBuilder::new(directory);
+    if let Some(err) = builder.add(&ignore_file) {
+        bail!("Failed reading '{}': {}", ignore_file.display(), err);
+    }
+    Ok(builder.build()?)
+}
+
+/// Returns whether the file at the given path has been modified within the given duration.
+/// Files whose modification time can't be read are considered recently modified so that
+/// they are not silently skipped
+fn modified_within(path: &path::Path, duration: Duration) -> bool {
+    match fs::metadata(path).and_then(|metadata| metadata.modified()) {
+        // Modification times in the future can't be older than the duration
+        Ok(modified) => modified
+            .elapsed()
+            .map_or(true, |elapsed| elapsed <= duration),
+        Err(err) => {
+            log::warn!(
+                "Could not read the modification time of '{}': {}",
+                path.display(),
+                err
+            );
+            true
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_directory::test_directory;
+    use std::sync::{Mutex, PoisonError};
+    use std::time::SystemTime;
+
+    /// Held while reading or changing the environment variables the command line falls back
+    /// to, so that tests parsing it never see the variables another test has set
+    static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+    /// Parses the command line arguments the way [`Cli::try_parse_from`] does while holding
+    /// the environment lock
+    pub(crate) fn try_parse_cli<'a>(
+        args: impl IntoIterator<Item = &'a str>,
+    ) -> Result<Cli, clap::Error> {
+        let _env_guard = ENV_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+        Cli::try_parse_from(args)
+    }
+
+    #[test]
+    fn parse_duration_test() {
+        assert_eq!(parse_duration("30s"), Ok(Duration::from_secs(30)));
+        assert_eq!(
+            parse_duration("2d"),
+            Ok(Duration::from_secs(2 * 24 * 60 * 60))
+        );
+        assert!(parse_duration("2").is_err());
+        assert!(parse_duration("d").is_err());
+        assert!(parse_duration("2y").is_err());
+    }
+
+    #[test]
+    fn describe_matching_test() {
+        let cli = try_parse_cli(["sub-auto-rename", "--print-regex"]).unwrap();
+        assert_eq!(
+            describe_matching(&cli),
+            "Built-in scanner, reading the signatures written as:\n\
+             - S01E02, S01 E02 or s01.e02, a letter like the \"a\" of S01E02a marking split episodes\n\
+             - Season 1 Episode 2, or Series 1 Episode 2\n\
+             - 1x02, standing on its own so that resolutions like 1920x1080 are left out\n\
+             - the season of a parent directory, like \"Season 1/Episode 2.srt\"\n"
+        );
+
+        let cli = try_parse_cli([
+            "sub-auto-rename",
+            "--print-regex",
+            "--pattern",
+            r"Vol(?P<season>\d+)-Part(?P<episode>\d+)",
+        ])
+        .unwrap();
+        assert_eq!(
+            describe_matching(&cli),
+            "Custom pattern: Vol(?P<season>\\d+)-Part(?P<episode>\\d+)\n"
+        );
+    }
+
+    #[test]
+    fn parse_pattern_test() {
+        assert!(parse_pattern(r"Vol(?P<season>\d+)-Part(?P<episode>\d+)").is_ok());
+        assert!(parse_pattern(r"Part(?P<episode>\d+)").is_err());
+        assert!(parse_pattern(r"(?P<season>\d+").is_err());
+    }
+
+    #[test]
+    fn modified_within_test() {
+        let directory = test_directory("modified-within");
+
+        let new_file_path = directory.join("new.srt");
+        let old_file_path = directory.join("old.srt");
+        fs::write(&new_file_path, "").unwrap();
+        let old_file = fs::File::create(&old_file_path).unwrap();
+        old_file
+            .set_modified(SystemTime::now() - Duration::from_secs(3 * 24 * 60 * 60))
+            .unwrap();
+
+        let since = parse_duration("2d").unwrap();
+        assert!(modified_within(&new_file_path, since));
+        assert!(!modified_within(&old_file_path, since));
+    }
+
+    #[test]
+    fn per_movie_subtitles_directory_test() {
+        let directory = test_directory("per-movie-subtitles");
+        let subs_directory = directory.join("Subs");
+        fs::create_dir_all(subs_directory.join("Show.S01E01.1080p")).unwrap();
+        fs::create_dir_all(subs_directory.join("Show.S01E02.1080p")).unwrap();
+        fs::write(directory.join("Show.S01E01.1080p.mkv"), "").unwrap();
+        fs::write(subs_directory.join("Show.S01E01.1080p/2_English.srt"), "").unwrap();
+        fs::write(subs_directory.join("Show.S01E02.1080p/2_English.srt"), "").unwrap();
+
+        assert!(is_per_movie_subtitles_directory(&subs_directory));
+        assert!(!is_per_movie_subtitles_directory(
+            &subs_directory.join("Show.S01E01.1080p")
+        ));
+
+        let subtitle_files = collect_per_movie_subtitle_files(
+            &subs_directory,
+            &ScanOptions::default(),
+            &ignore::gitignore::Gitignore::empty(),
+        );
+        assert_eq!(subtitle_files.len(), 2);
+
+        let movie_file = MovieFile::new(directory.join("Show.S01E01.1080p.mkv"), None).unwrap();
+        let renamed = subtitle_files
+            .iter()
+            .filter(|subtitle_file| {
+                matches!(
+                    subtitle_file.rename_using_movie_file(&movie_file),
+                    Ok(RenameOutcome::Moved(_))
+                )
+            })
+            .count();
+
+        assert_eq!(renamed, 1);
+        assert!(directory.join("Show.S01E01.1080p.srt").exists());
+    }
+
+    #[test]
+    fn flat_subtitles_directory_test() {
+        let directory = test_directory("flat-subtitles");
+        fs::create_dir_all(directory.join("Subs/Show.S01E03.1080p")).unwrap();
+        fs::write(directory.join("Show.S01E02.mkv"), "").unwrap();
+        fs::write(directory.join("Show.S01E03.1080p.mkv"), "").unwrap();
+        // Subtitle files kept directly in "Subs" next to per movie folders
+        fs::write(directory.join("Subs/show s01e02 web.srt"), "").unwrap();
+        fs::write(directory.join("Subs/Show.S01E03.1080p/English.srt"), "").unwrap();
+
+        let (movie_files, subtitle_files) = collect_files(
+            &directory,
+            &ScanOptions {
+                depth: walk_depth(true, None),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let rename_options = RenameOptions::default();
+        let mut planned_paths: Vec<_> = plan_pairs(&movie_files, &subtitle_files, &rename_options)
+            .into_iter()
+            .map(|(movie_index, subtitle_index)| {
+                subtitle_files[subtitle_index]
+                    .planned_path(&movie_files[movie_index], &rename_options)
+                    .unwrap()
+            })
+            .collect();
+        planned_paths.sort();
+
+        assert_eq!(
+            planned_paths,
+            vec![
+                directory.join("Show.S01E02.srt"),
+                directory.join("Show.S01E03.1080p.srt")
+            ]
+        );
+    }
+
+    #[test]
+    fn confirm_test() {
+        let mut output = Vec::new();
+        assert!(confirm("Continue?", true, &mut io::empty(), &mut output).unwrap());
+        assert!(output.is_empty());
+
+        let mut output = Vec::new();
+        assert!(confirm("Continue?", false, &mut "y\n".as_bytes(), &mut output).unwrap());
+        assert_eq!(String::from_utf8(output).unwrap(), "Continue? [y/N] ");
+
+        let mut output = Vec::new();
+        assert!(!confirm("Continue?", false, &mut "\n".as_bytes(), &mut output).unwrap());
+    }
+
+    #[test]
+    fn assume_yes_test() {
+        for args in [
+            &["sub-auto-rename", "--yes", "videos"][..],
+            &["sub-auto-rename", "--assume-yes", "videos"],
+            &["sub-auto-rename", "apply", "--plan-file", "plan.txt", "-y"],
+        ] {
+            let cli = try_parse_cli(args.iter().copied()).unwrap();
+            assert!(cli.yes);
+
+            // Nothing is read nor written when yes is assumed
+            let mut output = Vec::new();
+            assert!(confirm("Continue?", cli.yes, &mut io::empty(), &mut output).unwrap());
+            assert!(output.is_empty());
+        }
+    }
+
+    #[test]
+    fn environment_variables_test() {
+        // Held until the variables are removed, as other tests parse the command line
+        let _env_guard = ENV_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+        std::env::set_var("SAR_EXTRA_EXTENSIONS", "wmv,webm");
+        std::env::set_var("SAR_RETRIES", "3");
+
+        let cli = Cli::try_parse_from(["sub-auto-rename", "videos"]).unwrap();
+        assert_eq!(
+            cli.extra_movie_extensions,
+            Some(vec!["wmv".to_string(), "webm".to_string()])
+        );
+        assert_eq!(cli.retries, 3);
+
+        // Command line arguments take precedence over environment variables
+        let cli =
+            Cli::try_parse_from(["sub-auto-rename", "--retries", "1", "videos", "mpg"]).unwrap();
+        assert_eq!(cli.extra_movie_extensions, Some(vec!["mpg".to_string()]));
+        assert_eq!(cli.retries, 1);
+
+        std::env::remove_var("SAR_EXTRA_EXTENSIONS");
+        std::env::remove_var("SAR_RETRIES");
+    }
+
+    #[test]
+    fn find_duplicate_signatures_test() {
+        let movie_files: Vec<MovieFile> = [
+            "Show.S01E01.720p.mkv",
+            "Show.S01E02.720p.mkv",
+            "Show.S01E02.1080p.mkv",
+        ]
+        .into_iter()
+        .map(|name| MovieFile::new(path::PathBuf::from(name), None).unwrap())
+        .collect();
+
+        let duplicate_signatures = find_duplicate_signatures(&movie_files);
+
+        assert_eq!(duplicate_signatures.len(), 1);
+        assert_eq!(duplicate_signatures[0].0.to_string(), "S01E02");
+        assert_eq!(duplicate_signatures[0].1.len(), 2);
+    }
+
+    #[test]
+    fn plan_then_apply_test() {
+        let directory = test_directory("plan");
+        fs::write(directory.join("Show.S01E02.mkv"), "").unwrap();
+        fs::write(directory.join("show s01e02 web.srt"), "subtitle").unwrap();
+
+        let movie_files = vec![MovieFile::new(directory.join("Show.S01E02.mkv"), None).unwrap()];
+        let subtitle_files =
+            vec![SubtitleFile::try_from(directory.join("show s01e02 web.srt")).unwrap()];
+        let rename_options = RenameOptions::default();
+        let planned_pairs = plan_pairs(&movie_files, &subtitle_files, &rename_options);
+
+        let plan_file = directory.join("plan.tsv");
+        let mut writer = fs::File::create(&plan_file).unwrap();
+        plan::write_plan(
+            &mut writer,
+            &planned_renames(
+                &movie_files,
+                &subtitle_files,
+                &planned_pairs,
+                &rename_options,
+            ),
+        )
+        .unwrap();
+
+        assert_eq!(apply_plan(&plan_file, &rename_options).unwrap(), 1);
+        assert_eq!(
+            fs::read_to_string(directory.join("Show.S01E02.srt")).unwrap(),
+            "subtitle"
+        );
+        assert!(!directory.join("show s01e02 web.srt").exists());
+
+        // The renamed file is left alone when applying the plan again
+        assert_eq!(apply_plan(&plan_file, &rename_options).unwrap(), 0);
+    }
+
+    #[test]
+    fn collect_files_max_depth_test() {
+        let directory = test_directory("depth");
+        let nested_directory = directory.join("Season 1").join("extras");
+        fs::create_dir_all(&nested_directory).unwrap();
+        fs::write(directory.join("Show.S01E01.mkv"), "").unwrap();
+        fs::write(directory.join("Season 1").join("Show.S01E02.mkv"), "").unwrap();
+        fs::write(directory.join("Season 1").join("Show.S01E02.srt"), "").unwrap();
+        fs::write(nested_directory.join("Show.S01E03.mkv"), "").unwrap();
+
+        let count_files = |depth| {
+            let scan_options = ScanOptions {
+                depth,
+                ..Default::default()
+            };
+            let (movie_files, subtitle_files) = collect_files(&directory, &scan_options).unwrap();
+            (movie_files.len(), subtitle_files.len())
+        };
+
+        assert_eq!(count_files(walk_depth(false, None)), (1, 0));
+        assert_eq!(count_files(walk_depth(true, Some(0))), (1, 0));
+        assert_eq!(count_files(walk_depth(false, Some(1))), (2, 1));
+        assert_eq!(count_files(walk_depth(true, None)), (3, 1));
+    }
+
+    #[test]
+    fn plan_pairs_prefers_best_matches_test() {
+        // Two shows sharing the same signatures, the subtitle of the second show comes first
+        let movie_files: Vec<MovieFile> = ["First Show S01E02.mkv", "Second Show S01E02.mkv"]
+            .into_iter()
+            .map(|name| MovieFile::new(path::PathBuf::from(name), None).unwrap())
+            .collect();
+        let subtitle_files: Vec<SubtitleFile> = ["second.show.s01e02.srt", "first.show.s01e02.srt"]
+            .into_iter()
+            .map(|name| SubtitleFile::try_from(path::PathBuf::from(name)).unwrap())
+            .collect();
+
+        assert_eq!(
+            plan_pairs(&movie_files, &subtitle_files, &RenameOptions::default()),
+            vec![(0, 1), (1, 0)]
+        );
+    }
+
+    #[test]
+    fn sort_files_test() {
+        let directory = test_directory("sort");
+        let movie_files = vec![MovieFile::new(directory.join("Show.S01E02.mkv"), None).unwrap()];
+
+        // Subtitle files matching the movie file equally well
+        for (name, content, age_hours) in [
+            ("show s01e02 a.srt", "1", 3),
+            ("show s01e02 b.srt", "333", 2),
+            ("show s01e02 c.srt", "22", 0),
+        ] {
+            fs::File::create(directory.join(name))
+                .and_then(|mut file| {
+                    file.write_all(content.as_bytes())?;
+                    file.set_modified(SystemTime::now() - Duration::from_secs(age_hours * 60 * 60))
+                })
+                .unwrap();
+        }
+
+        for (sort, winner) in [
+            (SortKey::Name, "show s01e02 a.srt"),
+            (SortKey::Mtime, "show s01e02 c.srt"),
+            (SortKey::Size, "show s01e02 b.srt"),
+        ] {
+            let (_, mut subtitle_files) =
+                collect_files(&directory, &ScanOptions::default()).unwrap();
+            sort_files(&mut subtitle_files, SubtitleFile::path, sort);
+
+            let planned_pairs =
+                plan_pairs(&movie_files, &subtitle_files, &RenameOptions::default());
+            assert_eq!(planned_pairs.len(), 1);
+            assert_eq!(
+                subtitle_files[planned_pairs[0].1].path(),
+                directory.join(winner)
+            );
+        }
+    }
+
+    #[test]
+    fn plan_pairs_year_disambiguation_test() {
+        // A reboot and the original show sharing the same title and signatures
+        let movie_files: Vec<MovieFile> = ["Show (2019) S01E02.mkv", "Show (1995) S01E02.mkv"]
+            .into_iter()
+            .map(|name| MovieFile::new(path::PathBuf::from(name), None).unwrap())
+            .collect();
+        let subtitle_files: Vec<SubtitleFile> = ["Show.1995.S01E02.srt", "Show.2019.S01E02.srt"]
+            .into_iter()
+            .map(|name| SubtitleFile::try_from(path::PathBuf::from(name)).unwrap())
+            .collect();
+
+        assert_eq!(
+            plan_pairs(&movie_files, &subtitle_files, &RenameOptions::default()),
+            vec![(0, 1), (1, 0)]
+        );
+    }
+
+    #[test]
+    fn count_detected_signatures_test() {
+        let movie_files: Vec<MovieFile> = ["Show.S01E01.mkv", "Show 1x02.mkv", "Show.mkv"]
+            .into_iter()
+            .map(|name| MovieFile::new(path::PathBuf::from(name), None).unwrap())
+            .collect();
+        let subtitle_files: Vec<SubtitleFile> = ["show s01e01.srt", "English.srt"]
+            .into_iter()
+            .map(|name| SubtitleFile::try_from(path::PathBuf::from(name)).unwrap())
+            .collect();
+
+        let rename_options = RenameOptions::default();
+        assert_eq!(
+            count_detected_signatures(&movie_files, &subtitle_files, &rename_options),
+            (3, 5)
+        );
+        assert_eq!(count_detected_signatures(&[], &[], &rename_options), (0, 0));
+
+        // Names only read with the options, like a custom pattern, are detected too
+        let rename_options = RenameOptions {
+            pattern: Some(parse_pattern(r"Vol(?P<season>\d+)-Part(?P<episode>\d+)").unwrap()),
+            ..Default::default()
+        };
+        let movie_files =
+            vec![MovieFile::new(path::PathBuf::from("Show Vol1-Part2.mkv"), None).unwrap()];
+        assert_eq!(
+            count_detected_signatures(&movie_files, &[], &rename_options),
+            (1, 1)
+        );
+    }
+
+    #[test]
+    fn plan_pairs_by_index_test() {
+        // Files without any signature, not scanned in the order of their paths
+        let movie_files: Vec<MovieFile> = ["dir/2.mkv", "dir/1.mkv", "dir/3.mkv"]
+            .into_iter()
+            .map(|name| MovieFile::new(path::PathBuf::from(name), None).unwrap())
+            .collect();
+        let subtitle_files: Vec<SubtitleFile> = ["dir/b English.srt", "dir/a English.srt"]
+            .into_iter()
+            .map(|name| SubtitleFile::try_from(path::PathBuf::from(name)).unwrap())
+            .collect();
+
+        let planned_pairs = plan_pairs_by_index(&movie_files, &subtitle_files);
+        assert_eq!(planned_pairs, vec![(0, 0), (1, 1)]);
+
+        let rename_options = RenameOptions {
+            force: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            subtitle_files[1].planned_path(&movie_files[1], &rename_options),
+            Some(path::PathBuf::from("dir/1.srt"))
+        );
+    }
+
+    #[test]
+    fn plan_pairs_by_index_natural_order_test() {
+        // Unpadded movie file numbers against padded subtitle file numbers, past 9 so that a
+        // plain comparison of the names would put 10 before 2
+        let movie_files: Vec<MovieFile> = (1..=12)
+            .rev()
+            .map(|number| {
+                MovieFile::new(
+                    path::PathBuf::from(format!("dir/Show {}.mkv", number)),
+                    None,
+                )
+                .unwrap()
+            })
+            .collect();
+        let subtitle_files: Vec<SubtitleFile> = (1..=12)
+            .map(|number| {
+                SubtitleFile::try_from(path::PathBuf::from(format!(
+                    "dir/Show {:02} English.srt",
+                    number
+                )))
+                .unwrap()
+            })
+            .collect();
+
+        let planned_pairs = plan_pairs_by_index(&movie_files, &subtitle_files);
+        assert_eq!(planned_pairs.len(), 12);
+        for (movie_index, subtitle_index) in planned_pairs {
+            assert_eq!(movie_index, 11 - subtitle_index);
+        }
+    }
+
+    #[test]
+    fn natural_cmp_test() {
+        let natural_cmp = |first: &str, second: &str| {
+            natural_cmp(path::Path::new(first), path::Path::new(second))
+        };
+
+        assert!(natural_cmp("Show 2.mkv", "Show 10.mkv").is_lt());
+        assert!(natural_cmp("Show 02.mkv", "Show 10.mkv").is_lt());
+        assert!(natural_cmp("Show 2.mkv", "Show 02.mkv").is_lt());
+        assert!(natural_cmp("Show 10.mkv", "Show 9a.mkv").is_gt());
+        assert!(natural_cmp("a.mkv", "b.mkv").is_lt());
+        assert!(natural_cmp("Show.mkv", "Show 1.mkv").is_gt());
+        assert!(natural_cmp("Show 1", "Show 1.mkv").is_lt());
+        assert!(natural_cmp("Show 1.mkv", "Show 1.mkv").is_eq());
+    }
+
+    #[test]
+    fn plan_pairs_by_crc_test() {
+        let directory = test_directory("crc");
+        let movie_files: Vec<MovieFile> = ["[Group] Show [1080p].mkv", "[Group] Show [720p].mkv"]
+            .into_iter()
+            .zip(["first episode", "second episode"])
+            .map(|(name, content)| {
+                fs::write(directory.join(name), content).unwrap();
+                MovieFile::new(directory.join(name), None).unwrap()
+            })
+            .collect();
+        let subtitle_files: Vec<SubtitleFile> = ["Show - 28.srt", "Show - 27.srt", "Show - 29.srt"]
+            .into_iter()
+            .map(|name| SubtitleFile::try_from(directory.join(name)).unwrap())
+            .collect();
+
+        let crc_list = HashMap::from([
+            (crc_list::file_crc32(movie_files[0].path()).unwrap(), 27),
+            (crc_list::file_crc32(movie_files[1].path()).unwrap(), 28),
+        ]);
+        assert_eq!(
+            plan_pairs_by_crc(&movie_files, &subtitle_files, &crc_list).unwrap(),
+            vec![(0, 1), (1, 0)]
+        );
+    }
+
+    #[test]
+    fn dry_run_records_test() {
+        let movie_files: Vec<MovieFile> = ["Show.S01E01.mkv", "Show.S01E02.mkv"]
+            .into_iter()
+            .map(|name| MovieFile::new(path::PathBuf::from(name), None).unwrap())
+            .collect();
+        let subtitle_files: Vec<SubtitleFile> = ["Show.S01E01.srt", "show s01e02 web.srt"]
+            .into_iter()
+            .map(|name| SubtitleFile::try_from(path::PathBuf::from(name)).unwrap())
+            .collect();
+        let rename_options = RenameOptions::default();
+        let planned_pairs = plan_pairs(&movie_files, &subtitle_files, &rename_options);
+
+        let statuses: Vec<_> = dry_run_records(
+            &movie_files,
+            &subtitle_files,
+            &planned_pairs,
+            &rename_options,
+        )
+        .into_iter()
+        .map(|record| record.status)
+        .collect();
+
+        assert_eq!(
+            statuses,
+            vec![Some(RenameStatus::Skipped), Some(RenameStatus::WouldRename)]
+        );
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn compressed_subtitle_files_test() {
+        use std::io::Write;
+
+        let directory = test_directory("gzip");
+        fs::write(directory.join("Show.S01E02.mkv"), "").unwrap();
+        for file_name in ["show s01e02.srt.gz", "other s05e05.srt.gz"] {
+            let mut encoder = flate2::write::GzEncoder::new(
+                fs::File::create(directory.join(file_name)).unwrap(),
+                flate2::Compression::default(),
+            );
+            encoder.write_all(b"subtitle").unwrap();
+            encoder.finish().unwrap();
+        }
+        let directory_arg = directory.display().to_string();
+        let run = |args: &[&str]| {
+            let cli = try_parse_cli(
+                ["sub-auto-rename", "--ignore-number-difference"]
+                    .into_iter()
+                    .chain(args.iter().copied())
+                    .chain([directory_arg.as_str()]),
+            )
+            .unwrap();
+            rename_directory(
+                &cli,
+                &directory,
+                &RunOptions::from_cli(&cli),
+                &RenameOptions::default(),
+            )
+            .unwrap();
+        };
+
+        // A dry run leaves the compressed subtitle files alone
+        run(&["--dry-run"]);
+        assert!(directory.join("show s01e02.srt.gz").exists());
+        assert!(directory.join("other s05e05.srt.gz").exists());
+        assert!(!directory.join("show s01e02.srt").exists());
+        assert!(!directory.join("other s05e05.srt").exists());
+
+        // Only the renamed one is decompressed, the unmatched one is kept compressed
+        run(&[]);
+        assert_eq!(
+            fs::read_to_string(directory.join("Show.S01E02.srt")).unwrap(),
+            "subtitle"
+        );
+        assert!(!directory.join("show s01e02.srt.gz").exists());
+        assert!(directory.join("other s05e05.srt.gz").exists());
+        assert!(!directory.join("other s05e05.srt").exists());
+    }
+
+    #[test]
+    fn validate_directory_test() {
+        let directory = test_directory("validate");
+        let file_path = directory.join("Show.S01E02.mkv");
+        fs::write(&file_path, "").unwrap();
+
+        let mut directory_with_slash = directory.to_path_buf().into_os_string();
+        directory_with_slash.push("/");
+        assert_eq!(
+            validate_directory(path::Path::new(&directory_with_slash)).unwrap(),
+            *directory
+        );
+
+        let err = validate_directory(&file_path).unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("Expected a directory, got a file"));
+        assert!(validate_directory(&directory.join("missing")).is_err());
+    }
+
+    #[test]
+    fn write_json_summary_test() {
+        let directory = test_directory("summary");
+        let summary_json = directory.join("summary.json");
+        let movie_file = MovieFile::new(path::PathBuf::from("Show.S01E03.mkv"), None).unwrap();
+
+        write_json_summary(
+            &summary_json,
+            false,
+            &[RenameRecord::unmatched_movie(&movie_file)],
+        )
+        .unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&summary_json).unwrap(),
+            r#"{"dry_run":false,"results":[{"movie":"Show.S01E03.mkv","subtitle":"","old_name":"","new_name":"","status":"unmatched"}]}"#.to_string() + "\n"
+        );
+    }
+
+    #[test]
+    fn rename_planned_pair_isolates_errors_test() {
+        let directory = test_directory("isolate");
+        for episode in 1..=3 {
+            fs::write(directory.join(format!("Show.S01E0{}.mkv", episode)), "").unwrap();
+            fs::write(directory.join(format!("show s01e0{} web.srt", episode)), "").unwrap();
+        }
+
+        let movie_files: Vec<MovieFile> = (1..=3)
+            .map(|episode| {
+                MovieFile::new(directory.join(format!("Show.S01E0{}.mkv", episode)), None).unwrap()
+            })
+            .collect();
+        let subtitle_files: Vec<SubtitleFile> = (1..=3)
+            .map(|episode| {
+                SubtitleFile::try_from(directory.join(format!("show s01e0{} web.srt", episode)))
+                    .unwrap()
+            })
+            .collect();
+        // The second subtitle file disappears before being renamed
+        fs::remove_file(directory.join("show s01e02 web.srt")).unwrap();
+
+        let statuses: Vec<_> = movie_files
+            .iter()
+            .zip(&subtitle_files)
+            .map(|(movie_file, subtitle_file)| {
+                rename_planned_pair(movie_file, subtitle_file, &RenameOptions::default())
+            })
+            .collect();
+
+        assert_eq!(
+            statuses,
+            vec![
+                RenameStatus::Renamed,
+                RenameStatus::Failed,
+                RenameStatus::Renamed
+            ]
+        );
+        assert!(directory.join("Show.S01E01.srt").exists());
+        assert!(directory.join("Show.S01E03.srt").exists());
+
+        // A subtitle file whose new path is taken is skipped, not failed
+        let movie_path = directory.join("Show.S01E04.mkv");
+        let subtitle_path = directory.join("show s01e04 web.srt");
+        fs::write(&movie_path, "").unwrap();
+        fs::write(&subtitle_path, "").unwrap();
+        fs::write(directory.join("Show.S01E04.srt"), "existing").unwrap();
+        assert_eq!(
+            rename_planned_pair(
+                &MovieFile::new(movie_path, None).unwrap(),
+                &SubtitleFile::try_from(subtitle_path.clone()).unwrap(),
+                &RenameOptions::default()
+            ),
+            RenameStatus::Skipped
+        );
+        assert!(subtitle_path.exists());
+        assert_eq!(
+            fs::read_to_string(directory.join("Show.S01E04.srt")).unwrap(),
+            "existing"
+        );
+    }
+
+    #[test]
+    fn skip_subtitled_movie_files_test() {
+        let directory = test_directory("only-missing");
+        for file_name in [
+            "Show.S01E01.mkv",
+            "Show.S01E01.srt",
+            "Show.S01E02.mkv",
+            "show s01e02 web.srt",
+        ] {
+            fs::write(directory.join(file_name), "").unwrap();
+        }
+
+        let (mut movie_files, mut subtitle_files) =
+            collect_files(&directory, &ScanOptions::default()).unwrap();
+        skip_subtitled_movie_files(&mut movie_files, &mut subtitle_files);
+
+        assert_eq!(
+            movie_files
+                .iter()
+                .map(|movie_file| movie_file.path().to_path_buf())
+                .collect::<Vec<_>>(),
+            vec![directory.join("Show.S01E02.mkv")]
+        );
+        assert_eq!(
+            subtitle_files
+                .iter()
+                .map(|subtitle_file| subtitle_file.path().to_path_buf())
+                .collect::<Vec<_>>(),
+            vec![directory.join("show s01e02 web.srt")]
+        );
+    }
+
+    #[test]
+    fn rename_pair_signature_test() {
+        let directory = test_directory("pair-signature");
+        let movie_path = directory.join("Show.S03E04.mkv");
+        let subtitle_path = directory.join("Show.S01E01 English.srt");
+        fs::write(&movie_path, "").unwrap();
+        fs::write(&subtitle_path, "").unwrap();
+
+        let args = |signature| {
+            vec![
+                "sub-auto-rename".to_string(),
+                "pair".to_string(),
+                "--movie".to_string(),
+                movie_path.display().to_string(),
+                "--subtitle".to_string(),
+                subtitle_path.display().to_string(),
+                "--signature".to_string(),
+                signature,
+            ]
+        };
+        let rename = |signature: &str| {
+            let cli =
+                try_parse_cli(args(signature.to_string()).iter().map(String::as_str)).unwrap();
+            let Some(Command::Pair { signature, .. }) = cli.command else {
+                panic!("not the pair subcommand");
+            };
+            rename_pair(
+                &cli,
+                &movie_path,
+                &subtitle_path,
+                signature,
+                &RenameOptions::default(),
+            )
+        };
+
+        // The movie file is not the episode the subtitle file is said to be
+        assert!(rename("S03E05").is_err());
+        assert!(subtitle_path.exists());
+
+        // The signature of the subtitle file name is overridden
+        rename("S03E04").unwrap();
+        assert!(!subtitle_path.exists());
+        assert!(directory.join("Show.S03E04.srt").exists());
+
+        assert!(try_parse_cli(args("E04".to_string()).iter().map(String::as_str)).is_err());
+        let mut forced_args = args("S03E04".to_string());
+        forced_args.push("--force".to_string());
+        assert!(try_parse_cli(forced_args.iter().map(String::as_str)).is_err());
+    }
+
+    #[test]
+    fn streaming_conflicts_test() {
+        // Streaming renames as the files are found, it can't only report what it would do
+        for args in [
+            &["sub-auto-rename", "--streaming", "--dry-run", "videos"][..],
+            &["sub-auto-rename", "--streaming", "--match-only", "videos"],
+            &[
+                "sub-auto-rename",
+                "--streaming",
+                "--plan-file",
+                "plan.txt",
+                "videos",
+            ],
+            // Nor can it pair the files by their position
+            &[
+                "sub-auto-rename",
+                "--streaming",
+                "--rename-subtitles-to-index",
+                "videos",
+            ],
+        ] {
+            assert!(try_parse_cli(args.iter().copied()).is_err());
+        }
+    }
+
+    #[cfg(feature = "watch")]
+    #[test]
+    fn watch_renames_landing_files_test() {
+        use notify::Watcher;
+
+        let directory = test_directory("watch");
+        // Subtitled before the watch started, left alone by the runs of the watch
+        fs::write(directory.join("Show.S01E01.mkv"), "").unwrap();
+        fs::write(directory.join("Show.S01E01.srt"), "").unwrap();
+
+        let cli = try_parse_cli(["sub-auto-rename", "watch", directory.to_str().unwrap()]).unwrap();
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let mut watcher = notify::recommended_watcher(sender).unwrap();
+        watcher
+            .watch(&directory, notify::RecursiveMode::NonRecursive)
+            .unwrap();
+
+        let wait_for = |file_path: &path::Path| {
+            let deadline = std::time::Instant::now() + Duration::from_secs(10);
+            while !file_path.exists() {
+                assert!(std::time::Instant::now() < deadline);
+                std::thread::sleep(Duration::from_millis(20));
+            }
+        };
+
+        let runs = std::sync::atomic::AtomicUsize::new(0);
+        std::thread::scope(|scope| {
+            scope.spawn(|| {
+                let receiver = receiver;
+                watch::handle_changes(&receiver, Duration::from_millis(100), || {
+                    runs.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                    rename_directory(
+                        &cli,
+                        &directory,
+                        &RunOptions::watch(),
+                        &RenameOptions::default(),
+                    )
+                })
+            });
+
+            // The movie files land first, the run they trigger finds no subtitle file for them
+            fs::write(directory.join("Show.S01E02.mkv"), "").unwrap();
+            fs::write(directory.join("Show.S01E03.mkv"), "").unwrap();
+            let deadline = std::time::Instant::now() + Duration::from_secs(10);
+            while runs.load(std::sync::atomic::Ordering::SeqCst) == 0 {
+                assert!(std::time::Instant::now() < deadline);
+                std::thread::sleep(Duration::from_millis(20));
+            }
+
+            fs::write(directory.join("show s01e02.srt"), "subtitle").unwrap();
+            wait_for(&directory.join("Show.S01E02.srt"));
+
+            // Stops the watch once the changes seen so far are handled
+            drop(watcher);
+        });
+
+        assert_eq!(
+            fs::read_to_string(directory.join("Show.S01E02.srt")).unwrap(),
+            "subtitle"
+        );
+        assert!(!directory.join("show s01e02.srt").exists());
+        assert!(directory.join("Show.S01E01.srt").exists());
+    }
+
+    #[test]
+    fn log_level_test() {
+        for (args, level) in [
+            (&["sub-auto-rename", "videos"][..], log::LevelFilter::Info),
+            (
+                &["sub-auto-rename", "-v", "videos"],
+                log::LevelFilter::Debug,
+            ),
+            (
+                &["sub-auto-rename", "--summary-only", "videos"],
+                log::LevelFilter::Error,
+            ),
+            (
+                &["sub-auto-rename", "--summary-only", "-v", "videos"],
+                log::LevelFilter::Debug,
+            ),
+        ] {
+            let cli = try_parse_cli(args.iter().copied()).unwrap();
+            assert_eq!(log_level(&cli), level);
+        }
+    }
+
+    #[test]
+    fn summary_only_test() {
+        let movie_files = [MovieFile::new(path::PathBuf::from("Show.S01E02.mkv"), None).unwrap()];
+        let subtitle_files =
+            [SubtitleFile::try_from(path::PathBuf::from("show s01e02.srt")).unwrap()];
+        let rename_options = RenameOptions::default();
+        let records = dry_run_records(&movie_files, &subtitle_files, &[(0, 0)], &rename_options);
+        let focused_results = |args: &[&str]| {
+            let cli = try_parse_cli(args.iter().copied()).unwrap();
+            let mut output = Vec::new();
+            let printed = print_focused_results_to(&mut output, &cli, true, &records).unwrap();
+            printed.then(|| String::from_utf8(output).unwrap())
+        };
+
+        assert_eq!(
+            focused_results(&["sub-auto-rename", "--dry-run", "videos"]),
+            None
+        );
+        assert_eq!(
+            focused_results(&["sub-auto-rename", "--dry-run", "--summary-only", "videos"]).unwrap(),
+            "-------------- SUMMARY --------------\n\
+             Would rename     : 1\n\
+             Skipped          : 0\n\
+             Failed           : 0\n\
+             Unmatched subs   : 0\n\
+             Unmatched movies : 0\n"
+        );
+        assert_eq!(
+            focused_results(&[
+                "sub-auto-rename",
+                "--dry-run",
+                "--summary-only",
+                "--format",
+                "json",
+                "videos"
+            ])
+            .unwrap(),
+            "{\"dry_run\":true,\"renamed\":0,\"would_rename\":1,\"skipped\":0,\"failed\":0,\"unmatched_subs\":0,\"unmatched_movies\":0}\n"
+        );
+
+        // Nothing else is printed as the run goes, like the detected signatures or the renames
+        let cli = try_parse_cli(["sub-auto-rename", "videos"]).unwrap();
+        assert!(prints_progress(&cli));
+        let cli = try_parse_cli(["sub-auto-rename", "--summary-only", "videos"]).unwrap();
+        assert!(!prints_progress(&cli));
+    }
+
+    #[test]
+    fn report_unmatched_test() {
+        let movie_files = [
+            MovieFile::new(path::PathBuf::from("Show.S01E02.mkv"), None).unwrap(),
+            MovieFile::new(path::PathBuf::from("Show.S01E03.mkv"), None).unwrap(),
+        ];
+        let subtitle_files =
+            [SubtitleFile::try_from(path::PathBuf::from("show s01e02.srt")).unwrap()];
+        let rename_options = RenameOptions::default();
+        let records = dry_run_records(&movie_files, &subtitle_files, &[(0, 0)], &rename_options);
+        let focused_results = |args: &[&str]| {
+            let cli = try_parse_cli(args.iter().copied()).unwrap();
+            let mut output = Vec::new();
+            assert!(print_focused_results_to(&mut output, &cli, true, &records).unwrap());
+            String::from_utf8(output).unwrap()
+        };
+
+        // Only the unmatched movie file is printed, not the subtitle file that would be renamed
+        assert_eq!(
+            focused_results(&[
+                "sub-auto-rename",
+                "--dry-run",
+                "--report-unmatched",
+                "videos"
+            ]),
+            "Unmatched movie    : Show.S01E03.mkv\n"
+        );
+        assert_eq!(
+            focused_results(&[
+                "sub-auto-rename",
+                "--dry-run",
+                "--report-unmatched",
+                "--format",
+                "json",
+                "videos"
+            ]),
+            "{\"dry_run\":true,\"results\":[{\"movie\":\"Show.S01E03.mkv\",\"subtitle\":\"\",\"old_name\":\"\",\"new_name\":\"\",\"status\":\"unmatched\"}]}\n"
+        );
+
+        // Nothing else is printed as the run goes, like the detected signatures or the renames
+        let cli = try_parse_cli(["sub-auto-rename", "--report-unmatched", "videos"]).unwrap();
+        assert!(!prints_progress(&cli));
+    }
+
+    #[test]
+    fn rename_directory_failed_rename_test() {
+        let directory = test_directory("failed-rename");
+        fs::write(directory.join("Show.S01E02.mkv"), "").unwrap();
+        fs::write(directory.join("show s01e02 web.srt"), "").unwrap();
+        // A directory can't be replaced by the subtitle file
+        fs::create_dir_all(directory.join("Show.S01E02.srt/extras")).unwrap();
+        let report = directory.join("report.csv");
+        let summary_json = directory.join("summary.json");
+
+        let cli = try_parse_cli([
+            "sub-auto-rename",
+            "--on-conflict",
+            "overwrite",
+            "--report",
+            report.to_str().unwrap(),
+            "--summary-json-to",
+            summary_json.to_str().unwrap(),
+            directory.to_str().unwrap(),
+        ])
+        .unwrap();
+        rename_directory(
+            &cli,
+            &directory,
+            &RunOptions::from_cli(&cli),
+            &RenameOptions {
+                on_conflict: ConflictPolicy::Overwrite,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        // A single outcome for the pair, neither file of which is unmatched
+        assert_eq!(
+            fs::read_to_string(&report).unwrap(),
+            format!(
+                "movie,subtitle,old_name,new_name,status\n\
+                 {},{},show s01e02 web.srt,Show.S01E02.srt,failed\n",
+                directory.join("Show.S01E02.mkv").display(),
+                directory.join("show s01e02 web.srt").display()
+            )
+        );
+        let summary_json = fs::read_to_string(&summary_json).unwrap();
+        assert_eq!(summary_json.matches("\"status\":\"failed\"").count(), 1);
+        assert!(!summary_json.contains("unmatched"));
+        assert!(directory.join("show s01e02 web.srt").exists());
+    }
+
+    #[cfg(feature = "zip")]
+    #[test]
+    fn zipped_subtitle_files_test() {
+        let directory = test_directory("zipped-run");
+        let report = directory.join("report.csv");
+        // Both movie files match the archive, the most similar one gets it
+        fs::write(directory.join("Show.S01E02.720p.mkv"), "").unwrap();
+        fs::write(directory.join("Show.S01E02.1080p.WEB.mkv"), "").unwrap();
+        let archive_path = directory.join("show s01e02 1080p web.zip");
+        let mut writer = zip::ZipWriter::new(fs::File::create(&archive_path).unwrap());
+        writer
+            .start_file(
+                "show s01e02 1080p web.srt",
+                zip::write::SimpleFileOptions::default(),
+            )
+            .unwrap();
+        writer.write_all(b"subtitle").unwrap();
+        writer.finish().unwrap();
+
+        let directory_arg = directory.display().to_string();
+        let report_arg = report.display().to_string();
+        let run = |args: &[&str]| {
+            let cli = try_parse_cli(
+                [
+                    "sub-auto-rename",
+                    "--zipped-subtitles",
+                    "--ignore-number-difference",
+                    "--format",
+                    "json",
+                    "--report",
+                    &report_arg,
+                ]
+                .into_iter()
+                .chain(args.iter().copied())
+                .chain([directory_arg.as_str()]),
+            )
+            .unwrap();
+            rename_directory(
+                &cli,
+                &directory,
+                &RunOptions::from_cli(&cli),
+                &RenameOptions::default(),
+            )
+            .unwrap();
+            fs::read_to_string(&report).unwrap()
+        };
+        let zipped_subtitle_file =
+            format!("{} (show s01e02 1080p web.srt)", archive_path.display());
+
+        let report_lines = run(&["--dry-run"]);
+        assert!(report_lines.contains(&format!(
+            "{},{},show s01e02 1080p web.zip,Show.S01E02.1080p.WEB.zip,would_rename",
+            directory.join("Show.S01E02.1080p.WEB.mkv").display(),
+            zipped_subtitle_file
+        )));
+        assert!(archive_path.exists());
+
+        let report_lines = run(&[]);
+        assert!(report_lines.contains(&format!(
+            "{},{},show s01e02 1080p web.zip,Show.S01E02.1080p.WEB.zip,renamed",
+            directory.join("Show.S01E02.1080p.WEB.mkv").display(),
+            zipped_subtitle_file
+        )));
+        assert!(report_lines.contains(&format!(
+            "{},,,,unmatched",
+            directory.join("Show.S01E02.720p.mkv").display()
+        )));
+        assert!(!archive_path.exists());
+        assert!(directory.join("Show.S01E02.1080p.WEB.zip").exists());
+    }
+
+    #[test]
+    fn check_file_counts_test() {
+        let cli = try_parse_cli(["sub-auto-rename", "videos"]).unwrap();
+        let count_mismatch = RunOptions::from_cli(&cli).count_mismatch;
+        assert!(check_file_counts(2, 2, count_mismatch).is_ok());
+        assert!(check_file_counts(2, 1, count_mismatch).is_err());
+
+        let cli = try_parse_cli(["sub-auto-rename", "--warn-count-mismatch", "videos"]).unwrap();
+        let count_mismatch = RunOptions::from_cli(&cli).count_mismatch;
+        assert!(check_file_counts(2, 1, count_mismatch).is_ok());
+
+        let cli =
+            try_parse_cli(["sub-auto-rename", "--ignore-number-difference", "videos"]).unwrap();
+        let count_mismatch = RunOptions::from_cli(&cli).count_mismatch;
+        assert!(check_file_counts(2, 1, count_mismatch).is_ok());
+
+        assert!(try_parse_cli([
+            "sub-auto-rename",
+            "--warn-count-mismatch",
+            "--ignore-number-difference",
+            "videos"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn collect_files_ignore_file_test() {
+        let directory = test_directory("ignore");
+        fs::create_dir_all(directory.join("extras")).unwrap();
+        fs::create_dir_all(directory.join("Subs/Show.S01E02")).unwrap();
+        fs::create_dir_all(directory.join("Subs/Show.S01E03")).unwrap();
+        for file_name in [
+            "Show.S01E01.mkv",
+            "show s01e01 web.srt",
+            "show s01e01 web.sample.srt",
+            "extras/Show.S01E02.mkv",
+            "Subs/Show.S01E02/2_English.srt",
+            "Subs/Show.S01E02/3_English.sample.srt",
+            "Subs/Show.S01E03/2_English.srt",
+        ] {
+            fs::write(directory.join(file_name), "").unwrap();
+        }
+        // The patterns apply inside the per movie subtitle folders as well
+        fs::write(
+            directory.join(IGNORE_FILE_NAME),
+            "# samples\n*.sample.srt\nextras/\nSubs/Show.S01E03/\n",
+        )
+        .unwrap();
+
+        let (movie_files, subtitle_files) = collect_files(
+            &directory,
+            &ScanOptions {
+                depth: walk_depth(true, None),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            movie_files
+                .iter()
+                .map(|movie_file| movie_file.path().to_path_buf())
+                .collect::<Vec<_>>(),
+            vec![directory.join("Show.S01E01.mkv")]
+        );
+        assert_eq!(
+            subtitle_files
+                .iter()
+                .map(|subtitle_file| subtitle_file.path().to_path_buf())
+                .collect::<Vec<_>>(),
+            vec![
+                directory.join("Subs/Show.S01E02/2_English.srt"),
+                directory.join("show s01e01 web.srt")
+            ]
+        );
+    }
+
+    #[test]
+    fn collect_files_excluded_extensions_test() {
+        let directory = test_directory("exclude");
+        fs::write(directory.join("Show.S01E01.mkv"), "").unwrap();
+        fs::write(directory.join("Show.S01E01.3gp"), "").unwrap();
+        fs::write(directory.join("Show.S01E02.3GP"), "").unwrap();
+
+        let excluded_movie_extensions = vec!["3gp".to_string()];
+        let (movie_files, subtitle_files) = collect_files(
+            &directory,
+            &ScanOptions {
+                excluded_movie_extensions: &excluded_movie_extensions,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            movie_files
+                .iter()
+                .map(|movie_file| movie_file.path().to_path_buf())
+                .collect::<Vec<_>>(),
+            vec![directory.join("Show.S01E01.mkv")]
+        );
+        assert!(subtitle_files.is_empty());
+    }
+}
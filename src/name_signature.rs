@@ -1,81 +1,1012 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
 use std::ffi::OsStr;
+use std::ops::Range;
+use thiserror::Error;
 
 /// Whether or not Episode signature matches
 #[derive(Debug, PartialEq)]
 pub enum MatchSignature {
+    /// Both names have the same season and episode signature
     Match,
+    /// The names have different signatures, or at least one of them has none
     NoMatch,
 }
 
+impl MatchSignature {
+    /// Returns whether the signatures match
+    pub fn is_match(&self) -> bool {
+        matches!(self, Self::Match)
+    }
+}
+
 /// Checks if the two file names have the same episodic signature, that is S01E02 signature
-/// matches on both files, return the match signature
-pub fn episode_name_signature_check(first_name: &OsStr, second_name: &OsStr) -> MatchSignature {
-    let first_name = first_name.to_string_lossy().to_string().to_lowercase();
-    let second_name = second_name.to_string_lossy().to_string().to_lowercase();
-
-    let first_name_season = get_signature_value(SignatureType::Season, &first_name);
-    let first_name_episode = get_signature_value(SignatureType::Episode, &first_name);
-    let second_name_season = get_signature_value(SignatureType::Season, &second_name);
-    let second_name_episode = get_signature_value(SignatureType::Episode, &second_name);
-
-    let mut seasons_matched = false;
-    let mut episodes_matched = false;
-
-    if let Some(first_name_season) = first_name_season {
-        if let Some(second_name_season) = second_name_season {
-            if first_name_season == second_name_season {
-                seasons_matched = true
+/// matches on both files, return the match signature.
+/// Only the file name component of the given names is scanned, that way directories in
+/// the path don't affect the signatures. The exception is the immediate parent directory
+/// when named like "Season 02", which provides the season to file names only having the
+/// episode, like "Season 02/Show E05.mkv".
+/// Names only having an episode number, which is the absolute numbering across all seasons,
+/// get the signature that number is mapped to in the episode map.
+/// Names that aren't valid UTF-8 are scanned after replacing their invalid bytes, the ASCII
+/// signatures are left intact but the invalid bytes can't be part of titles or signatures.
+/// When "#" numbers are enabled, names without any signature, like "Show #102", match names
+/// having the same absolute episode number.
+/// The episode offset of the options only shifts the episode of the second name
+pub fn episode_name_signature_check(
+    first_name: &OsStr,
+    second_name: &OsStr,
+    options: &SignatureOptions,
+) -> MatchSignature {
+    let first_key = get_match_key(first_name, options, false);
+    let second_key = get_match_key(second_name, options, true);
+
+    // Split episodes like E02a and E02b share the same number but are different episodes,
+    // comparing the whole signatures takes care of that
+    let match_signature = match (first_key, second_key) {
+        (Some(first_key), Some(second_key)) if first_key == second_key => MatchSignature::Match,
+        _ => MatchSignature::NoMatch,
+    };
+
+    // The arguments are only evaluated when trace logging is enabled, so this costs nothing
+    // on regular runs
+    log::trace!(
+        "{}",
+        describe_comparison(
+            first_name,
+            first_key,
+            second_name,
+            second_key,
+            &match_signature
+        )
+    );
+
+    match_signature
+}
+
+/// What names are matched on by [`episode_name_signature_check`], two names matching when
+/// they have the same key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MatchKey {
+    /// The signature of the name, mapped from its absolute episode by the episode map when
+    /// the name has none
+    Signature(Signature),
+    /// The absolute episode of names without any signature when "#" numbers are enabled, like
+    /// the 102 of "Show #102"
+    AbsoluteEpisode(u32),
+}
+
+/// Returns the key the name is matched on, see [`MatchKey`], or None when the name has nothing
+/// to be matched on. The episode offset of the options is only applied when `offset` is true,
+/// as it only shifts the episode of the second name of [`episode_name_signature_check`]
+pub fn get_match_key(name: &OsStr, options: &SignatureOptions, offset: bool) -> Option<MatchKey> {
+    let episode_offset = if offset { options.episode_offset } else { 0 };
+
+    if let Some(signature) = get_mapped_signature(name, options) {
+        return Some(MatchKey::Signature(Signature {
+            episode: signature.episode.checked_add_signed(episode_offset)?,
+            ..signature
+        }));
+    }
+
+    if options.hash_episodes {
+        return get_absolute_episode(name, options)
+            .and_then(|episode| episode.checked_add_signed(episode_offset))
+            .map(MatchKey::AbsoluteEpisode);
+    }
+
+    None
+}
+
+/// Same as [`episode_name_signature_check`] with the default options, for names held in
+/// plain strings, like when the matcher is used without any filesystem
+pub fn match_names(first_name: &str, second_name: &str) -> MatchSignature {
+    episode_name_signature_check(
+        OsStr::new(first_name),
+        OsStr::new(second_name),
+        &SignatureOptions::default(),
+    )
+}
+
+/// Checks whether both names have the same episode number, whatever their seasons, like
+/// "Show.S01E05.mkv" and "Show.S02E05.srt". Names without a season, like "Show E05.srt", have
+/// their episode number compared as well
+pub fn same_episode_number(first_name: &str, second_name: &str) -> bool {
+    let episode = |name| {
+        let (_, file_name) = split_file_name_component(name);
+        get_signature_value(SignatureType::Episode, file_name)
+    };
+
+    match (episode(first_name), episode(second_name)) {
+        (Some(first_episode), Some(second_episode)) => first_episode == second_episode,
+        _ => false,
+    }
+}
+
+/// Checks whether the titles leading the file names, like "Breaking Bad" in
+/// "Breaking.Bad.S01E02.mkv", clearly differ, that is when both names have a title and
+/// neither title is the start of the other, ignoring separators, and case unless
+/// `case_sensitive` is true.
+/// The title is made of the words before the first word having a digit or being a signature
+/// word, so years and signatures are not part of it. A leading season, like the "s01" of
+/// "s01.Show.e02", is skipped
+pub fn titles_differ(first_name: &OsStr, second_name: &OsStr, case_sensitive: bool) -> bool {
+    let first_name = first_name.to_string_lossy();
+    let second_name = second_name.to_string_lossy();
+    let first_title = get_title_words(split_file_name_component(&first_name).1, case_sensitive);
+    let second_title = get_title_words(split_file_name_component(&second_name).1, case_sensitive);
+
+    if first_title.is_empty() || second_title.is_empty() {
+        return false;
+    }
+
+    let shared_len = first_title.len().min(second_title.len());
+    first_title[..shared_len] != second_title[..shared_len]
+}
+
+/// Returns the title leading the file name, like "Breaking Bad" for "Breaking.Bad.S01E02.mkv",
+/// its words being joined with spaces. Returns None when the name has no title, see
+/// [`titles_differ`]
+pub fn show_title(file_name: &OsStr) -> Option<String> {
+    let file_name = file_name.to_string_lossy();
+    let title_words = get_title_words(split_file_name_component(&file_name).1, true);
+
+    if title_words.is_empty() {
+        None
+    } else {
+        Some(title_words.join(" "))
+    }
+}
+
+/// Returns the words of the title leading the file name, lowercased unless `case_sensitive`
+/// is true, see [`titles_differ`]
+fn get_title_words(file_name: &str, case_sensitive: bool) -> Vec<String> {
+    strip_leading_groups(file_name)
+        .split(|x: char| !x.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        // Names splitting the signature around the title, like "s01.Show.e02", lead with the
+        // season
+        .skip_while(|word| is_compact_season_word(word))
+        .take_while(|word| is_title_word(word))
+        .map(|word| {
+            if case_sensitive {
+                word.to_string()
+            } else {
+                word.to_lowercase()
             }
+        })
+        .collect()
+}
+
+/// Returns the title leading the file name the way it is written, like "Grey's.Anatomy" for
+/// "Grey's.Anatomy.2005.S01E02", along with the year following it, if any. Returns None when
+/// the name has no title, see [`titles_differ`]
+pub fn get_title_and_year(file_name: &str) -> Option<(&str, Option<u32>)> {
+    let words: Vec<&str> = strip_leading_groups(file_name)
+        .split(|x: char| !x.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .skip_while(|word| is_compact_season_word(word))
+        .collect();
+    let title_len = words.iter().take_while(|word| is_title_word(word)).count();
+    if title_len == 0 {
+        return None;
+    }
+
+    let title = &file_name
+        [byte_range(file_name, words[0])?.start..byte_range(file_name, words[title_len - 1])?.end];
+    let year = words
+        .get(title_len)
+        .filter(|word| word.len() == 4)
+        .and_then(|word| word.parse().ok())
+        .filter(|year| (1900..=2099).contains(year));
+
+    Some((title, year))
+}
+
+/// Returns the file name without the "[...]" and "(...)" groups leading it, like the release
+/// group of "[Group] Show.S01E02.mkv", so that they are not taken for the title
+fn strip_leading_groups(file_name: &str) -> &str {
+    let mut rest = file_name;
+
+    loop {
+        let trimmed =
+            rest.trim_start_matches(|x: char| !x.is_alphanumeric() && x != '[' && x != '(');
+        let closing = match trimmed.chars().next() {
+            Some('[') => ']',
+            Some('(') => ')',
+            _ => return rest,
+        };
+
+        match trimmed.find(closing) {
+            Some(index) => rest = &trimmed[index + 1..],
+            None => return rest,
         }
     }
-    if let Some(first_name_episode) = first_name_episode {
-        if let Some(second_name_episode) = second_name_episode {
-            if first_name_episode == second_name_episode {
-                episodes_matched = true
-            }
+}
+
+/// Returns whether the word can be part of a title, that is when it has no digit and is not a
+/// signature word
+fn is_title_word(word: &str) -> bool {
+    let word = word.to_lowercase();
+    !word.contains(|x: char| x.is_ascii_digit())
+        && !SEASON_WORDS.contains(&word.as_str())
+        && !EPISODE_WORDS.contains(&word.as_str())
+}
+
+/// Returns whether the word is a compact season on its own, like "s01"
+fn is_compact_season_word(word: &str) -> bool {
+    word.strip_prefix(['s', 'S'])
+        .is_some_and(|digits| !digits.is_empty() && digits.chars().all(|x| x.is_ascii_digit()))
+}
+
+/// Returns the key a name is matched on the way it is shown in logs
+fn describe_match_key(match_key: Option<MatchKey>) -> String {
+    match match_key {
+        Some(MatchKey::Signature(signature)) => signature.to_string(),
+        Some(MatchKey::AbsoluteEpisode(episode)) => format!("#{}", episode),
+        None => "no signature".to_string(),
+    }
+}
+
+/// Returns the comparison of two names the way it is shown in trace logs
+fn describe_comparison(
+    first_name: &OsStr,
+    first_key: Option<MatchKey>,
+    second_name: &OsStr,
+    second_key: Option<MatchKey>,
+    match_signature: &MatchSignature,
+) -> String {
+    format!(
+        "Compared '{}' [{}] with '{}' [{}]: {:?}",
+        first_name.to_string_lossy(),
+        describe_match_key(first_key),
+        second_name.to_string_lossy(),
+        describe_match_key(second_key),
+        match_signature
+    )
+}
+
+/// The season and episode signature of a file name, like S01E02
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Signature {
+    /// The season number
+    pub season: u32,
+    /// The episode number
+    pub episode: u32,
+    /// The letter following the episode number of split episodes, like the "a" in S01E02a
+    pub episode_suffix: Option<char>,
+}
+
+impl Signature {
+    /// Parses the signature of the given name, like "S01E02", returning None when the name has
+    /// no season and episode signature
+    pub fn parse(name: &str) -> Option<Self> {
+        get_signature(OsStr::new(name))
+    }
+}
+
+impl std::fmt::Display for Signature {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "S{:02}E{:02}", self.season, self.episode)?;
+        if let Some(episode_suffix) = self.episode_suffix {
+            write!(f, "{}", episode_suffix)?;
+        }
+        Ok(())
+    }
+}
+
+/// Options changing how signatures are read from names
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SignatureOptions<'a> {
+    /// Absolute episode numbers mapped to the signatures of the episodes they stand for
+    pub episode_map: Option<&'a HashMap<u32, Signature>>,
+    /// Whether to read seasons written in Roman numerals, like "Season IV" or "S.IV". This is
+    /// opt-in as words of titles can be mistaken for Roman numerals
+    pub roman_numeral_seasons: bool,
+    /// Whether to read volumes, like "Vol.1" or "Volume 1", as seasons. This is opt-in as
+    /// some releases number their volumes independently of the seasons
+    pub volume_seasons: bool,
+    /// Extra markers of seasons, read when names have no built-in season marker, like "t" for
+    /// the Spanish "temporada" in "T01C02". Markers longer than a character, like "temporada",
+    /// may be separated from the number as in "Temporada 1"
+    pub season_markers: &'a [String],
+    /// Extra markers of episodes, read when names have no built-in episode marker, like "c"
+    /// for the Spanish "capítulo" in "T01C02"
+    pub episode_markers: &'a [String],
+    /// A number added to the episode of the second name when comparing signatures, for
+    /// subtitle packs numbering the episodes off by one
+    pub episode_offset: i32,
+    /// Whether to read four digits codes like "0102" as signatures, like S01E02, when names
+    /// have no other signature. This is opt-in as years like "1999" look the same
+    pub numeric_code: bool,
+    /// Whether to read "#" numbers, like the "#102" of "Show #102", as absolute episode
+    /// numbers and to match names without signatures on their absolute episode numbers
+    pub hash_episodes: bool,
+    /// The season of the names only having an episode, like "Show.E02" for miniseries
+    pub default_season: Option<u32>,
+    /// A regex with "season" and "episode" named groups reading the signatures instead of
+    /// the built-in scanner
+    #[cfg(feature = "pattern")]
+    pub pattern: Option<&'a regex::Regex>,
+}
+
+/// Returns the season and episode signature of the file name, if it has both.
+/// The season is read from the immediate parent "Season NN" directory when the file name
+/// only has the episode
+pub fn get_signature(name: &OsStr) -> Option<Signature> {
+    get_signature_with_options(name, &SignatureOptions::default())
+}
+
+/// A part of a name a season or episode value is read from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignaturePart {
+    /// The text of the part, like "01"
+    pub text: String,
+    /// The byte range of the part in the name
+    pub range: Range<usize>,
+}
+
+/// The parts of a name the season and episode values are read from, see [`inspect_signature`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignatureInspection {
+    /// The part the season is read from, if any
+    pub season: Option<SignaturePart>,
+    /// The part the episode is read from, if any
+    pub episode: Option<SignaturePart>,
+}
+
+impl SignatureInspection {
+    /// Returns why the name has no signature, telling where the part that has been found is,
+    /// or None when the name has both a season and an episode
+    pub fn error(&self) -> Option<SignatureError> {
+        match (&self.season, &self.episode) {
+            (Some(_), Some(_)) => None,
+            (Some(season), None) => Some(SignatureError::EpisodeMissing {
+                season: season.range.clone(),
+            }),
+            (None, Some(episode)) => Some(SignatureError::SeasonMissing {
+                episode: episode.range.clone(),
+            }),
+            (None, None) => Some(SignatureError::NotFound),
         }
     }
+}
+
+/// Why a name has no signature, see [`SignatureInspection::error`]
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum SignatureError {
+    /// Neither a season nor an episode has been found
+    #[error("Neither a season nor an episode has been found")]
+    NotFound,
+
+    /// A season has been found at the given byte range of the name, but no episode
+    #[error("A season has been found at bytes {}..{} but no episode", .season.start, .season.end)]
+    EpisodeMissing {
+        /// The byte range of the season in the name
+        season: Range<usize>,
+    },
+
+    /// An episode has been found at the given byte range of the name, but no season
+    #[error("An episode has been found at bytes {}..{} but no season", .episode.start, .episode.end)]
+    SeasonMissing {
+        /// The byte range of the episode in the name
+        episode: Range<usize>,
+    },
+}
+
+/// Returns the parts of the name the built-in scanner reads the season and episode from, meant
+/// for diagnostics. The ranges are byte ranges of the name, lossily converted to UTF-8
+pub fn inspect_signature(name: &OsStr) -> SignatureInspection {
+    let name = name.to_string_lossy();
+    let (directory_name, file_name) = split_file_name_component(&name);
+    let part = |digits: &str| {
+        Some(SignaturePart {
+            text: digits.to_string(),
+            range: byte_range(&name, digits)?,
+        })
+    };
+
+    let season = get_signature_digits(SignatureType::Season, file_name).or_else(|| {
+        directory_name.and_then(|directory_name| {
+            get_verbose_signature_digits(SignatureType::Season, directory_name)
+        })
+    });
+
+    SignatureInspection {
+        season: season.and_then(part),
+        episode: get_signature_digits(SignatureType::Episode, file_name).and_then(part),
+    }
+}
+
+/// Returns the byte index right after the episode signature of the file name, including the
+/// letter of split episodes, like the index right after "S01E02" in "Show.S01E02.The.Title"
+pub fn get_signature_end(file_name: &str) -> Option<usize> {
+    let digits = get_signature_digits(SignatureType::Episode, file_name)?;
+    let episode_suffix = get_episode_suffix(file_name);
+
+    Some(byte_range(file_name, digits)?.end + episode_suffix.map_or(0, char::len_utf8))
+}
+
+/// Returns the file name with the separators between the season and the episode of its
+/// signature removed, like "Show.S01E02" for "Show.S01 E02", or None when the signature of the
+/// name is not written that way
+pub fn compact_signature(file_name: &str) -> Option<String> {
+    let season = byte_range(
+        file_name,
+        get_signature_digits(SignatureType::Season, file_name)?,
+    )?;
+    let episode = byte_range(
+        file_name,
+        get_signature_digits(SignatureType::Episode, file_name)?,
+    )?;
+    if season.end >= episode.start {
+        return None;
+    }
+
+    let between = &file_name[season.end..episode.start];
+    let episode_marker = between.trim_start_matches(VERBOSE_SEPARATORS);
+    if episode_marker.len() == between.len() || !episode_marker.eq_ignore_ascii_case("e") {
+        return None;
+    }
+
+    Some(format!(
+        "{}{}{}",
+        &file_name[..season.end],
+        episode_marker,
+        &file_name[episode.start..]
+    ))
+}
+
+/// Returns the file name with the season written before its episode, like "Show.S01E02" for
+/// "Show.E02" and the season 1, or None when the name has a season or no episode written like
+/// "E02"
+pub fn add_default_season(file_name: &str, season: u32) -> Option<String> {
+    if get_signature_digits(SignatureType::Season, file_name).is_some() {
+        return None;
+    }
 
-    if seasons_matched && episodes_matched {
-        MatchSignature::Match
+    let episode = byte_range(
+        file_name,
+        get_compact_signature_digits(SignatureType::Episode, file_name)?,
+    )?;
+    // The index of the episode marker, which is ASCII
+    let marker_index = episode.start.checked_sub(1)?;
+    let season_marker = if file_name[marker_index..].starts_with('e') {
+        's'
     } else {
-        MatchSignature::NoMatch
+        'S'
+    };
+
+    Some(format!(
+        "{}{}{:02}{}",
+        &file_name[..marker_index],
+        season_marker,
+        season,
+        &file_name[marker_index..]
+    ))
+}
+
+/// Returns the byte range the part, which has to be a slice of the name, takes in the name.
+/// Returns None when the part is not a slice of the name
+fn byte_range(name: &str, part: &str) -> Option<Range<usize>> {
+    let start = (part.as_ptr() as usize).checked_sub(name.as_ptr() as usize);
+    let range = start
+        .map(|start| start..start + part.len())
+        .filter(|range| range.end <= name.len());
+    debug_assert!(range.is_some(), "'{}' is not a slice of '{}'", part, name);
+    range
+}
+
+/// Same as [`get_signature`], applying the given options
+fn get_signature_with_options(name: &OsStr, options: &SignatureOptions) -> Option<Signature> {
+    let name = name.to_string_lossy();
+    let name = to_ascii_digits(&name);
+    let (directory_name, file_name) = split_file_name_component(&name);
+
+    #[cfg(feature = "pattern")]
+    if let Some(pattern) = options.pattern {
+        return get_pattern_signature(pattern, file_name);
+    }
+
+    // The opt-in ways of writing seasons
+    let get_optional_season_value = |name| {
+        options
+            .roman_numeral_seasons
+            .then(|| get_roman_numeral_season_value(name))
+            .flatten()
+            .or_else(|| {
+                options
+                    .volume_seasons
+                    .then(|| get_volume_season_value(name))
+                    .flatten()
+            })
+    };
+
+    let season = get_signature_value(SignatureType::Season, file_name)
+        .or_else(|| get_optional_season_value(file_name))
+        .or_else(|| get_marker_value(options.season_markers, file_name))
+        .or_else(|| {
+            directory_name.and_then(|directory_name| {
+                get_verbose_signature_value(SignatureType::Season, directory_name)
+                    .or_else(|| get_optional_season_value(directory_name))
+            })
+        })
+        .or(options.default_season);
+
+    let episode = get_signature_value(SignatureType::Episode, file_name)
+        .or_else(|| get_marker_value(options.episode_markers, file_name));
+
+    match (season, episode) {
+        (Some(season), Some(episode)) => Some(Signature {
+            season,
+            episode,
+            episode_suffix: get_episode_suffix(file_name),
+        }),
+        _ => options
+            .numeric_code
+            .then(|| get_numeric_code_signature(file_name))
+            .flatten(),
+    }
+}
+
+/// Returns the signature written as a four digits "SSEE" code, like "0102" for S01E02. The
+/// code has to stand on its own so that things like the "1080p" resolution are not picked up
+fn get_numeric_code_signature(name: &str) -> Option<Signature> {
+    for (index, _) in name.match_indices(|x: char| x.is_ascii_digit()) {
+        let (before, rest) = name.split_at(index);
+        let code = prefix_while(rest, |x| x.is_ascii_digit());
+
+        let stands_alone = !before.ends_with(char::is_alphanumeric)
+            && !rest[code.len()..].starts_with(char::is_alphanumeric);
+
+        if code.len() == 4 && stands_alone {
+            // SAFETY: the code has been checked to be made of four ASCII digits
+            return Some(Signature {
+                season: code[..2].parse().unwrap(),
+                episode: code[2..].parse().unwrap(),
+                episode_suffix: None,
+            });
+        }
+    }
+
+    None
+}
+
+/// Returns the signature captured by the "season" and "episode" named groups of the pattern
+/// in the file name, the groups have to capture numbers
+#[cfg(feature = "pattern")]
+fn get_pattern_signature(pattern: &regex::Regex, file_name: &str) -> Option<Signature> {
+    let captures = pattern.captures(file_name)?;
+    let value = |group| captures.name(group)?.as_str().parse::<u32>().ok();
+
+    Some(Signature {
+        season: value("season")?,
+        episode: value("episode")?,
+        episode_suffix: None,
+    })
+}
+
+/// Returns the signature of the file name, or the signature its absolute episode number is
+/// mapped to when it has no season
+fn get_mapped_signature(name: &OsStr, options: &SignatureOptions) -> Option<Signature> {
+    get_signature_with_options(name, options).or_else(|| {
+        let episode_map = options.episode_map?;
+        get_absolute_episode(name, options)
+            .and_then(|absolute_episode| episode_map.get(&absolute_episode).copied())
+    })
+}
+
+/// Returns the episode number of the file name when it has no season, as is the case for
+/// names numbering the episodes continuously across seasons, like "Show E27.srt" or the anime
+/// style "[Group] Show - 27 [1080p][ABCD1234].mkv". Bracketed groups, like the release group,
+/// the resolution or the CRC hash of anime names, are not scanned
+pub fn get_absolute_episode(name: &OsStr, options: &SignatureOptions) -> Option<u32> {
+    if get_signature_with_options(name, options).is_some() {
+        return None;
+    }
+
+    let name = name.to_string_lossy();
+    let name = to_ascii_digits(&name);
+    let (_, file_name) = split_file_name_component(&name);
+    let file_name = strip_bracketed_groups(file_name);
+    get_signature_value(SignatureType::Episode, &file_name)
+        .or_else(|| get_dashed_episode_value(&file_name))
+        .or_else(|| {
+            options
+                .hash_episodes
+                .then(|| get_hash_episode_value(&file_name))
+                .flatten()
+        })
+}
+
+/// Returns the episode number following a "#", as in "Show #102". The number has to stand on
+/// its own so that things like "#1080p" are not picked up
+fn get_hash_episode_value(name: &str) -> Option<u32> {
+    for (index, _) in name.match_indices('#') {
+        let rest = &name[index + 1..];
+        let value_str = prefix_while(rest, |x| x.is_ascii_digit());
+
+        if !rest[value_str.len()..].starts_with(char::is_alphanumeric) {
+            if let Ok(value) = value_str.parse() {
+                return Some(value);
+            }
+        }
+    }
+
+    None
+}
+
+/// Returns the name without its "[...]" groups
+fn strip_bracketed_groups(name: &str) -> String {
+    let mut stripped_name = String::with_capacity(name.len());
+    let mut depth = 0_usize;
+
+    for x in name.chars() {
+        match x {
+            '[' => depth += 1,
+            ']' if depth > 0 => depth -= 1,
+            _ if depth == 0 => stripped_name.push(x),
+            _ => {}
+        }
+    }
+
+    stripped_name
+}
+
+/// Returns the bare episode number written between " - " and a space or the extension, as in
+/// the anime style "Show - 02 .mkv" once the bracketed groups are stripped. A version suffix,
+/// as in "Show - 02v2", is allowed
+fn get_dashed_episode_value(name: &str) -> Option<u32> {
+    for (index, delimiter) in name.match_indices(" - ") {
+        let rest = &name[index + delimiter.len()..];
+        let value_str = prefix_while(rest, |x| x.is_ascii_digit());
+        let after = &rest[value_str.len()..];
+
+        let is_version =
+            after.starts_with('v') && after[1..].starts_with(|x: char| x.is_ascii_digit());
+        if after.is_empty() || after.starts_with([' ', '.']) || is_version {
+            if let Ok(value) = value_str.parse::<u32>() {
+                return Some(value);
+            }
+        }
+    }
+
+    None
+}
+
+/// Checks if the file name has both season and episode signatures
+pub fn has_episode_signature(name: &OsStr, options: &SignatureOptions) -> bool {
+    get_signature_with_options(name, options).is_some()
+}
+
+/// Returns the name with the decimal digits of other scripts, like the Arabic-Indic "٠١٢",
+/// replaced with their ASCII counterparts so that their values get read like ASCII ones
+fn to_ascii_digits(name: &str) -> Cow<'_, str> {
+    let ascii_digit = |x: char| {
+        if !x.is_numeric() || x.is_ascii_digit() {
+            return None;
+        }
+        UNICODE_ZERO_DIGITS.iter().find_map(|zero| {
+            let value = (x as u32).checked_sub(*zero as u32)?;
+            (value < 10).then(|| char::from(b'0' + value as u8))
+        })
+    };
+
+    if !name.chars().any(|x| ascii_digit(x).is_some()) {
+        return Cow::Borrowed(name);
     }
+    Cow::Owned(name.chars().map(|x| ascii_digit(x).unwrap_or(x)).collect())
 }
 
+/// Splits the name into the name of its immediate parent directory, if any, and the part of
+/// the name after the last path separator. Both '/' and '\' are treated as separators
+/// regardless of the platform so that Windows paths, including the extended-length `\\?\C:\`
+/// and UNC `\\server\share` forms, are handled everywhere
+fn split_file_name_component(name: &str) -> (Option<&str>, &str) {
+    let mut components = name.rsplit(['/', '\\']);
+    // SAFETY: splitting always yields at least one component, even on empty strings
+    let file_name = components.next().unwrap();
+
+    (components.next(), file_name)
+}
+
+#[derive(Clone, Copy)]
 enum SignatureType {
     Season,
     Episode,
 }
 
+/// Words that can be used in place of the season signature character, as in "Season 1"
+/// or the British "Series 1"
+const SEASON_WORDS: &[&str] = &["season", "series"];
+
+/// Words that can be used in place of the episode signature character, as in "Episode 2"
+const EPISODE_WORDS: &[&str] = &["episode"];
+
+/// Words of volumes read as seasons when enabled, as in "Vol.1", the longest words come first
+const VOLUME_WORDS: &[&str] = &["volume", "vol"];
+
+/// The zero digits of the scripts whose decimal digits are read in names, each followed by the
+/// nine other digits of the script
+const UNICODE_ZERO_DIGITS: &[char] = &[
+    '\u{0660}', // Arabic-Indic
+    '\u{06F0}', // Extended Arabic-Indic, used for Persian and Urdu
+    '\u{0966}', // Devanagari
+    '\u{09E6}', // Bengali
+    '\u{0E50}', // Thai
+    '\u{FF10}', // Fullwidth
+];
+
+/// Characters allowed between a verbose signature word and its value
+const VERBOSE_SEPARATORS: &[char] = &[' ', '.', '_', '-'];
+
+/// Returns the leading part of the string whose characters satisfy the predicate
+fn prefix_while(string: &str, predicate: impl Fn(char) -> bool) -> &str {
+    let end = string.find(|x: char| !predicate(x)).unwrap_or(string.len());
+    &string[..end]
+}
+
+/// Returns the byte indices at which the ASCII word occurs in the name, ignoring case
+fn match_indices_ignore_ascii_case<'a>(
+    name: &'a str,
+    word: &'a str,
+) -> impl Iterator<Item = usize> + 'a {
+    name.as_bytes()
+        .windows(word.len())
+        .enumerate()
+        .filter(move |(_, window)| window.eq_ignore_ascii_case(word.as_bytes()))
+        .map(|(index, _)| index)
+}
+
+/// Returns the digits of season/episode in the given string when written in the compact form,
+/// as in "S01E02"
+fn get_compact_signature_digits(signature_type: SignatureType, name: &str) -> Option<&str> {
+    let chars_to_check = match signature_type {
+        SignatureType::Season => ['s', 'S'],
+        SignatureType::Episode => ['e', 'E'],
+    };
+
+    // Only what follows the signature characters is checked, that way names starting with
+    // numbers, like "2_English.srt", are not mistaken for signatures
+    for (index, _) in name.match_indices(chars_to_check) {
+        // The signature characters are ASCII so the index right after them is a char boundary
+        let rest = &name[index + 1..];
+        let value_str = prefix_while(rest, char::is_numeric);
+
+        if !value_str.is_empty() {
+            // The numeric characters may still fail to parse, like when the value is too big
+            // to fit in u32 on pathological names, in such case there is no signature value
+            return value_str.parse::<u32>().is_ok().then_some(value_str);
+        }
+    }
+
+    None
+}
+
+/// Returns the letter following the episode number of split episodes, like the "a" in "S01E02a".
+/// The letter has to stand on its own so that things like "S01E02x264" are not picked up
+fn get_episode_suffix(name: &str) -> Option<char> {
+    let digits = get_compact_signature_digits(SignatureType::Episode, name)?;
+    let mut chars = name[byte_range(name, digits)?.end..].chars();
+
+    match (chars.next(), chars.next()) {
+        (Some(suffix), next) if suffix.is_ascii_alphabetic() => match next {
+            Some(next) if next.is_alphanumeric() => None,
+            _ => Some(suffix.to_ascii_lowercase()),
+        },
+        _ => None,
+    }
+}
+
 /// Returns the value of season/episode in the given string, this is specified
 /// via it's signature type parameter
 fn get_signature_value(signature_type: SignatureType, name: &str) -> Option<u32> {
-    let char_to_check = match signature_type {
-        SignatureType::Season => 's',
-        SignatureType::Episode => 'e',
+    get_signature_digits(signature_type, name).and_then(|digits| digits.parse().ok())
+}
+
+/// Returns the digits the value of season/episode is read from in the given string, the
+/// digits always parse into u32
+fn get_signature_digits(signature_type: SignatureType, name: &str) -> Option<&str> {
+    get_compact_signature_digits(signature_type, name)
+        .or_else(|| get_verbose_signature_digits(signature_type, name))
+        .or_else(|| {
+            get_cross_signature_digits(name).map(|(season, episode)| match signature_type {
+                SignatureType::Season => season,
+                SignatureType::Episode => episode,
+            })
+        })
+}
+
+/// Returns the digits of season and episode in the given string when written in the
+/// "1x02" notation. The notation has to stand on its own, like "Show 1x02" or "Show [1x02]",
+/// so that things like the "1920x1080" resolution are not picked up
+fn get_cross_signature_digits(name: &str) -> Option<(&str, &str)> {
+    for (index, _) in name.match_indices(['x', 'X']) {
+        let (before, after) = (&name[..index], &name[index + 1..]);
+
+        let season_len = before
+            .chars()
+            .rev()
+            .take_while(|x| x.is_ascii_digit())
+            .count();
+        let episode_len = after.chars().take_while(|x| x.is_ascii_digit()).count();
+
+        if !(1..=2).contains(&season_len) || !(1..=3).contains(&episode_len) {
+            continue;
+        }
+
+        let (before, season_str) = before.split_at(before.len() - season_len);
+        let (episode_str, after) = after.split_at(episode_len);
+
+        let stands_alone =
+            !before.ends_with(char::is_alphanumeric) && !after.starts_with(char::is_alphanumeric);
+
+        // Both strings have been checked to only contain digits and be short enough to fit
+        // into u32
+        if stands_alone {
+            return Some((season_str, episode_str));
+        }
+    }
+
+    None
+}
+
+/// Returns the value of season/episode in the given string when written in words,
+/// that is "Season 1 Episode 2" instead of "S01E02"
+fn get_verbose_signature_value(signature_type: SignatureType, name: &str) -> Option<u32> {
+    get_verbose_signature_digits(signature_type, name).and_then(|digits| digits.parse().ok())
+}
+
+/// Returns the digits of season/episode in the given string when written in words
+fn get_verbose_signature_digits(signature_type: SignatureType, name: &str) -> Option<&str> {
+    let words_to_check = match signature_type {
+        SignatureType::Season => SEASON_WORDS,
+        SignatureType::Episode => EPISODE_WORDS,
     };
 
-    let mut value = None;
+    for word in words_to_check {
+        for index in match_indices_ignore_ascii_case(name, word) {
+            // The word is ASCII so the index right after it is always a char boundary
+            let value_str = prefix_while(
+                name[index + word.len()..].trim_start_matches(VERBOSE_SEPARATORS),
+                |x| x.is_ascii_digit(),
+            );
+
+            if value_str.parse::<u32>().is_ok() {
+                return Some(value_str);
+            }
+        }
+    }
 
-    for chunk in name.split(char_to_check) {
-        let value_str: String = chunk.chars().take_while(|x| x.is_numeric()).collect();
+    None
+}
 
-        if !value_str.is_empty() {
-            // SAFETY: all the characters in the string have been checked if they are numeric
-            // hence calling unwrap here is safe
-            value = Some(value_str.parse::<u32>().unwrap());
-            break;
+/// Returns the value of the season in the given string when written in Roman numerals, as in
+/// "Season IV" or "S.IV". Only uppercase numerals standing on their own are read
+fn get_roman_numeral_season_value(name: &str) -> Option<u32> {
+    for word in SEASON_WORDS {
+        for index in match_indices_ignore_ascii_case(name, word) {
+            // The word is ASCII so the index right after it is always a char boundary
+            let rest = &name[index + word.len()..];
+            if rest.starts_with(VERBOSE_SEPARATORS) {
+                if let Some(value) =
+                    parse_roman_numeral(rest.trim_start_matches(VERBOSE_SEPARATORS))
+                {
+                    return Some(value);
+                }
+            }
+        }
+    }
+
+    // The compact "S.IV" form, the "S" has to start a word so that names like "Shows.IV"
+    // are not picked up
+    for (index, _) in name.match_indices(['s', 'S']) {
+        let starts_word = !name[..index].ends_with(char::is_alphanumeric);
+        if let Some(rest) = name[index + 1..].strip_prefix('.') {
+            if starts_word {
+                if let Some(value) = parse_roman_numeral(rest) {
+                    return Some(value);
+                }
+            }
+        }
+    }
+
+    None
+}
+
+/// Returns the number following the first of the markers found in the given string, the case
+/// of the markers being ignored. The marker can't follow a letter, so that the "t" of "Part01"
+/// is not picked up while the "C" of "T01C02" is, and markers longer than a character may be
+/// separated from the number, as in "Temporada 1"
+fn get_marker_value(markers: &[String], name: &str) -> Option<u32> {
+    for marker in markers.iter().filter(|marker| !marker.is_empty()) {
+        for index in match_indices_ignore_ascii_case(name, marker) {
+            if !name.is_char_boundary(index)
+                || !name.is_char_boundary(index + marker.len())
+                || name[..index].ends_with(char::is_alphabetic)
+            {
+                continue;
+            }
+
+            let rest = &name[index + marker.len()..];
+            let rest = if marker.chars().count() > 1 {
+                rest.trim_start_matches(VERBOSE_SEPARATORS)
+            } else {
+                rest
+            };
+            if let Ok(value) = prefix_while(rest, |x| x.is_ascii_digit()).parse() {
+                return Some(value);
+            }
+        }
+    }
+
+    None
+}
+
+/// Returns the value of the season in the given string when written as a volume, as in
+/// "Vol.1" or "Volume 1". The word has to start a word so that names like "Evolution 2"
+/// are not picked up
+fn get_volume_season_value(name: &str) -> Option<u32> {
+    for word in VOLUME_WORDS {
+        for index in match_indices_ignore_ascii_case(name, word) {
+            if name[..index].ends_with(char::is_alphanumeric) {
+                continue;
+            }
+
+            // The word is ASCII so the index right after it is always a char boundary
+            let value_str = prefix_while(
+                name[index + word.len()..].trim_start_matches(VERBOSE_SEPARATORS),
+                |x| x.is_ascii_digit(),
+            );
+            if let Ok(value) = value_str.parse() {
+                return Some(value);
+            }
+        }
+    }
+
+    None
+}
+
+/// Parses the uppercase Roman numeral at the start of the string, which has to be followed by
+/// a non alphanumeric character or the end of the string
+fn parse_roman_numeral(string: &str) -> Option<u32> {
+    let numeral = prefix_while(string, |x| "IVXLC".contains(x));
+    if numeral.is_empty() || string[numeral.len()..].starts_with(char::is_alphanumeric) {
+        return None;
+    }
+
+    let digit_value = |digit| match digit {
+        'I' => 1,
+        'V' => 5,
+        'X' => 10,
+        'L' => 50,
+        _ => 100,
+    };
+
+    let mut digit_values = numeral.chars().map(digit_value).peekable();
+    let mut value = 0;
+    while let Some(digit_value) = digit_values.next() {
+        // A digit followed by a bigger one is subtracted, as in "IV"
+        match digit_values.peek() {
+            Some(next_digit_value) if *next_digit_value > digit_value => {
+                value -= digit_value as i64
+            }
+            _ => value += digit_value as i64,
         }
     }
 
-    value
+    u32::try_from(value).ok().filter(|value| *value > 0)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    /// Checks the names without any episode map, as most of the tests do
+    fn episode_name_signature_check(first_name: &OsStr, second_name: &OsStr) -> MatchSignature {
+        super::episode_name_signature_check(first_name, second_name, &SignatureOptions::default())
+    }
+
     #[test]
     fn episode_name_signature_check_test() {
         let name_1 = OsStr::new("Hellos01e02mov");
@@ -138,4 +1069,1006 @@ mod tests {
             1
         );
     }
+
+    #[test]
+    fn episode_name_signature_check_verbose_test() {
+        let name_1 = OsStr::new("Show Season 1 Episode 2.mkv");
+        let name_2 = OsStr::new("Show Series 1 Episode 2.mkv");
+        let name_3 = OsStr::new("Show.S01E02.srt");
+        let name_4 = OsStr::new("Show Series 1 Episode 3.mkv");
+
+        assert_eq!(
+            episode_name_signature_check(name_1, name_3),
+            MatchSignature::Match
+        );
+        assert_eq!(
+            episode_name_signature_check(name_2, name_3),
+            MatchSignature::Match
+        );
+        assert_eq!(
+            episode_name_signature_check(name_4, name_3),
+            MatchSignature::NoMatch
+        );
+    }
+
+    #[test]
+    fn episode_name_signature_check_bracketed_test() {
+        let name_1 = OsStr::new("Show [1x02].mkv");
+        let name_2 = OsStr::new("Show 1x02.srt");
+        let name_3 = OsStr::new("Show (S01E02).srt");
+        let name_4 = OsStr::new("Show.S01E02.srt");
+        let name_5 = OsStr::new("Show [1x03] 1920x1080.mkv");
+
+        assert_eq!(
+            episode_name_signature_check(name_1, name_2),
+            MatchSignature::Match
+        );
+        assert_eq!(
+            episode_name_signature_check(name_1, name_3),
+            MatchSignature::Match
+        );
+        assert_eq!(
+            episode_name_signature_check(name_3, name_4),
+            MatchSignature::Match
+        );
+        assert_eq!(
+            episode_name_signature_check(name_5, name_4),
+            MatchSignature::NoMatch
+        );
+    }
+
+    #[test]
+    fn episode_name_signature_check_long_name_test() {
+        let long_name = format!("{}.S01E02.mkv", "a".repeat(10_000));
+        let overflowing_name = format!("show.s{}e02.mkv", "9".repeat(100));
+        let short_name = "show.s01e02.srt";
+
+        assert_eq!(
+            episode_name_signature_check(OsStr::new(&long_name), OsStr::new(short_name)),
+            MatchSignature::Match
+        );
+        assert_eq!(
+            episode_name_signature_check(OsStr::new(&overflowing_name), OsStr::new(short_name)),
+            MatchSignature::NoMatch
+        );
+    }
+
+    #[test]
+    fn episode_name_signature_check_windows_path_test() {
+        let name_1 = OsStr::new(r"\\?\C:\Shows\S09E09\show.s01e02.mkv");
+        let name_2 = OsStr::new(r"\\server\share\Season 5\show s01e02 web.srt");
+        let name_3 = OsStr::new(r"C:\Shows\show.s01e02.srt");
+
+        assert_eq!(
+            episode_name_signature_check(name_1, name_2),
+            MatchSignature::Match
+        );
+        assert_eq!(
+            episode_name_signature_check(name_1, name_3),
+            MatchSignature::Match
+        );
+        assert_eq!(
+            split_file_name_component(r"\\server\share\show.srt"),
+            (Some("share"), "show.srt")
+        );
+    }
+
+    #[test]
+    fn episode_name_signature_check_zero_test() {
+        let name_1 = OsStr::new("show.s01e00.mkv");
+        let name_2 = OsStr::new("show s01e00 pilot.srt");
+        let name_3 = OsStr::new("show.s00e00.mkv");
+        let name_4 = OsStr::new("show.s00e00.srt");
+        let name_5 = OsStr::new("show.s01e01.srt");
+
+        assert_eq!(
+            episode_name_signature_check(name_1, name_2),
+            MatchSignature::Match
+        );
+        assert_eq!(
+            episode_name_signature_check(name_3, name_4),
+            MatchSignature::Match
+        );
+        assert_eq!(
+            episode_name_signature_check(name_1, name_5),
+            MatchSignature::NoMatch
+        );
+        assert_eq!(
+            get_signature_value(SignatureType::Episode, "show.s01e00.mkv"),
+            Some(0)
+        );
+        assert_eq!(
+            get_signature_value(SignatureType::Season, "show.s00e01.mkv"),
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn has_episode_signature_test() {
+        assert!(has_episode_signature(
+            OsStr::new("show.s01e02.srt"),
+            &SignatureOptions::default()
+        ));
+        assert!(!has_episode_signature(
+            OsStr::new("Subs/Show.S01E02/English.srt"),
+            &SignatureOptions::default()
+        ));
+    }
+
+    #[test]
+    fn name_starting_with_number_test() {
+        // The leading number is not preceded by a signature character
+        assert_eq!(
+            get_compact_signature_digits(SignatureType::Season, "2_English.srt"),
+            None
+        );
+        assert_eq!(
+            get_compact_signature_digits(SignatureType::Season, "12_Show_S03E04.srt"),
+            Some("03")
+        );
+        assert_eq!(
+            get_compact_signature_digits(SignatureType::Episode, "12_Show_S03E04.srt"),
+            Some("04")
+        );
+    }
+
+    #[test]
+    fn episode_name_signature_check_split_episode_test() {
+        let name_1 = OsStr::new("show.s01e02a.mkv");
+        let name_2 = OsStr::new("show s01e02a.srt");
+        let name_3 = OsStr::new("show.s01e02b.srt");
+        let name_4 = OsStr::new("show.s01e02.x264.mkv");
+        let name_5 = OsStr::new("show.s01e02x264.srt");
+
+        assert_eq!(
+            episode_name_signature_check(name_1, name_2),
+            MatchSignature::Match
+        );
+        assert_eq!(
+            episode_name_signature_check(name_1, name_3),
+            MatchSignature::NoMatch
+        );
+        assert_eq!(
+            episode_name_signature_check(name_4, name_5),
+            MatchSignature::Match
+        );
+        assert_eq!(get_episode_suffix("show.s01e02a.mkv"), Some('a'));
+        assert_eq!(get_episode_suffix("show.s01e02.mkv"), None);
+    }
+
+    #[test]
+    fn get_signature_test() {
+        let signature = get_signature(OsStr::new("Show.S01E02a.mkv")).unwrap();
+
+        assert_eq!(
+            signature,
+            Signature {
+                season: 1,
+                episode: 2,
+                episode_suffix: Some('a')
+            }
+        );
+        assert_eq!(signature.to_string(), "S01E02a");
+        assert_eq!(get_signature(OsStr::new("Show.E02.mkv")), None);
+    }
+
+    #[test]
+    fn episode_name_signature_check_season_directory_test() {
+        let name_1 = OsStr::new("Show/Season 02/Show E05.mkv");
+        let name_2 = OsStr::new("Show/Season 02/Show.E05.srt");
+        let name_3 = OsStr::new("Show/Season 03/Show.E05.srt");
+        let name_4 = OsStr::new("Show/Extras/Show.E05.srt");
+
+        assert_eq!(
+            episode_name_signature_check(name_1, name_2),
+            MatchSignature::Match
+        );
+        assert_eq!(
+            episode_name_signature_check(name_1, name_3),
+            MatchSignature::NoMatch
+        );
+        assert_eq!(
+            episode_name_signature_check(name_1, name_4),
+            MatchSignature::NoMatch
+        );
+        assert_eq!(get_signature(name_1).unwrap().to_string(), "S02E05");
+    }
+
+    #[test]
+    fn describe_comparison_test() {
+        let first_name = OsStr::new("Traced.Show.S03E04.mkv");
+        let second_name = OsStr::new("Traced.Show.E04.srt");
+        let options = SignatureOptions::default();
+
+        assert_eq!(
+            describe_comparison(
+                first_name,
+                get_match_key(first_name, &options, false),
+                second_name,
+                get_match_key(second_name, &options, true),
+                &episode_name_signature_check(first_name, second_name)
+            ),
+            "Compared 'Traced.Show.S03E04.mkv' [S03E04] with 'Traced.Show.E04.srt' [no signature]: NoMatch"
+        );
+    }
+
+    #[test]
+    fn episode_name_signature_check_episode_map_test() {
+        let episode_map = HashMap::from([(27, Signature::parse("S02E05").unwrap())]);
+        let options = SignatureOptions {
+            episode_map: Some(&episode_map),
+            ..Default::default()
+        };
+        let movie_name = OsStr::new("Show.S02E05.mkv");
+
+        assert_eq!(
+            super::episode_name_signature_check(movie_name, OsStr::new("Show E27.srt"), &options),
+            MatchSignature::Match
+        );
+        assert_eq!(
+            super::episode_name_signature_check(movie_name, OsStr::new("Show E28.srt"), &options),
+            MatchSignature::NoMatch
+        );
+        assert_eq!(
+            episode_name_signature_check(movie_name, OsStr::new("Show E27.srt")),
+            MatchSignature::NoMatch
+        );
+        assert_eq!(
+            get_absolute_episode(movie_name, &SignatureOptions::default()),
+            None
+        );
+    }
+
+    #[test]
+    fn match_signature_is_match_test() {
+        assert!(MatchSignature::Match.is_match());
+        assert!(!MatchSignature::NoMatch.is_match());
+    }
+
+    #[test]
+    fn episode_name_signature_check_roman_numeral_test() {
+        let options = SignatureOptions {
+            roman_numeral_seasons: true,
+            ..Default::default()
+        };
+        let movie_name = OsStr::new("Show.S04E02.mkv");
+
+        for subtitle_name in ["Show Season IV Episode 2.srt", "Show S.IV E02.srt"] {
+            assert_eq!(
+                super::episode_name_signature_check(
+                    movie_name,
+                    OsStr::new(subtitle_name),
+                    &options
+                ),
+                MatchSignature::Match
+            );
+            // Roman numerals are opt-in
+            assert_eq!(
+                episode_name_signature_check(movie_name, OsStr::new(subtitle_name)),
+                MatchSignature::NoMatch
+            );
+        }
+
+        assert_eq!(
+            super::episode_name_signature_check(
+                OsStr::new("Show.S01E02.mkv"),
+                OsStr::new("Show Season IX Episode 2.srt"),
+                &options
+            ),
+            MatchSignature::NoMatch
+        );
+        assert_eq!(parse_roman_numeral("XIV"), Some(14));
+        assert_eq!(parse_roman_numeral("IVy"), None);
+    }
+
+    #[test]
+    fn episode_name_signature_check_volume_test() {
+        let options = SignatureOptions {
+            volume_seasons: true,
+            ..Default::default()
+        };
+        let movie_name = OsStr::new("Show.S01E02.mkv");
+
+        for subtitle_name in ["Show Vol.1 E02.srt", "Show Volume 1 Episode 2.srt"] {
+            assert_eq!(
+                super::episode_name_signature_check(
+                    movie_name,
+                    OsStr::new(subtitle_name),
+                    &options
+                ),
+                MatchSignature::Match
+            );
+            // Volumes are opt-in
+            assert_eq!(
+                episode_name_signature_check(movie_name, OsStr::new(subtitle_name)),
+                MatchSignature::NoMatch
+            );
+        }
+
+        assert_eq!(
+            super::episode_name_signature_check(
+                movie_name,
+                OsStr::new("Show Vol.2 E02.srt"),
+                &options
+            ),
+            MatchSignature::NoMatch
+        );
+        assert_eq!(get_volume_season_value("Evolution 2 E02"), None);
+        assert_eq!(get_volume_season_value("Show.VOL-3.E01"), Some(3));
+    }
+
+    #[test]
+    fn episode_name_signature_check_markers_test() {
+        let season_markers = ["t".to_string(), "temporada".to_string()];
+        let episode_markers = ["c".to_string(), "capitulo".to_string()];
+        let options = SignatureOptions {
+            season_markers: &season_markers,
+            episode_markers: &episode_markers,
+            ..Default::default()
+        };
+        let movie_name = OsStr::new("Show.S01E02.mkv");
+
+        for subtitle_name in ["Show.T01C02.srt", "Show Temporada 1 Capitulo 2.srt"] {
+            assert_eq!(
+                super::episode_name_signature_check(
+                    movie_name,
+                    OsStr::new(subtitle_name),
+                    &options
+                ),
+                MatchSignature::Match
+            );
+            // Extra markers are opt-in
+            assert_eq!(
+                episode_name_signature_check(movie_name, OsStr::new(subtitle_name)),
+                MatchSignature::NoMatch
+            );
+        }
+
+        assert_eq!(
+            super::episode_name_signature_check(
+                movie_name,
+                OsStr::new("Show.T02C02.srt"),
+                &options
+            ),
+            MatchSignature::NoMatch
+        );
+        // Markers can't follow a letter
+        assert_eq!(get_marker_value(&season_markers, "Part01"), None);
+    }
+
+    #[test]
+    fn episode_name_signature_check_year_test() {
+        let name_1 = OsStr::new("Show (2019) S01E02.mkv");
+        let name_2 = OsStr::new("Show.2019.S01E02.WEB.srt");
+        let name_3 = OsStr::new("Show (2019) S01E03.srt");
+
+        assert_eq!(
+            episode_name_signature_check(name_1, name_2),
+            MatchSignature::Match
+        );
+        assert_eq!(
+            episode_name_signature_check(name_1, name_3),
+            MatchSignature::NoMatch
+        );
+        assert_eq!(get_signature(name_1).unwrap().to_string(), "S01E02");
+        assert_eq!(get_signature(OsStr::new("Show (2019).mkv")), None);
+    }
+
+    #[test]
+    fn episode_name_signature_check_mixed_case_test() {
+        let name_1 = OsStr::new("Show.S01E02.mkv");
+        let name_2 = OsStr::new("show.s01e02.srt");
+        let name_3 = OsStr::new("Show.s01E02.srt");
+        let name_4 = OsStr::new("SHOW SEASON 1 episode 2.srt");
+
+        for name in [name_2, name_3, name_4] {
+            assert_eq!(
+                episode_name_signature_check(name_1, name),
+                MatchSignature::Match
+            );
+        }
+        assert_eq!(get_signature(name_2), get_signature(name_1));
+    }
+
+    #[cfg(feature = "pattern")]
+    #[test]
+    fn episode_name_signature_check_pattern_test() {
+        let pattern = regex::Regex::new(r"Vol(?P<season>\d+)-Part(?P<episode>\d+)").unwrap();
+        let options = SignatureOptions {
+            pattern: Some(&pattern),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            super::episode_name_signature_check(
+                OsStr::new("Show Vol2-Part05.mkv"),
+                OsStr::new("show.vol.Vol02-Part5.srt"),
+                &options
+            ),
+            MatchSignature::Match
+        );
+        assert_eq!(
+            super::episode_name_signature_check(
+                OsStr::new("Show Vol2-Part05.mkv"),
+                OsStr::new("Show Vol2-Part06.srt"),
+                &options
+            ),
+            MatchSignature::NoMatch
+        );
+        // The pattern replaces the built-in scanner
+        assert_eq!(
+            super::episode_name_signature_check(
+                OsStr::new("Show.S02E05.mkv"),
+                OsStr::new("Show.S02E05.srt"),
+                &options
+            ),
+            MatchSignature::NoMatch
+        );
+    }
+
+    #[test]
+    fn inspect_signature_test() {
+        let inspection = inspect_signature(OsStr::new("Show.S01E02.mkv"));
+        assert_eq!(
+            inspection.season,
+            Some(SignaturePart {
+                text: "01".to_string(),
+                range: 6..8
+            })
+        );
+        assert_eq!(
+            inspection.episode,
+            Some(SignaturePart {
+                text: "02".to_string(),
+                range: 9..11
+            })
+        );
+
+        let inspection = inspect_signature(OsStr::new("Season 02/Show E05.mkv"));
+        assert_eq!(inspection.season.map(|part| part.range), Some(7..9));
+        assert_eq!(inspection.episode.map(|part| part.range), Some(16..18));
+
+        let inspection = inspect_signature(OsStr::new("Show.mkv"));
+        assert_eq!(inspection.season, None);
+        assert_eq!(inspection.episode, None);
+    }
+
+    #[test]
+    fn episode_name_signature_check_episode_offset_test() {
+        let movie_name = OsStr::new("Show.S01E02.mkv");
+        let check = |subtitle_name, episode_offset| {
+            super::episode_name_signature_check(
+                movie_name,
+                OsStr::new(subtitle_name),
+                &SignatureOptions {
+                    episode_offset,
+                    ..Default::default()
+                },
+            )
+        };
+
+        assert_eq!(check("Show.S01E01.srt", 1), MatchSignature::Match);
+        assert_eq!(check("Show.S01E02.srt", 1), MatchSignature::NoMatch);
+        assert_eq!(check("Show.S01E03.srt", -1), MatchSignature::Match);
+        assert_eq!(check("Show.S01E00.srt", -1), MatchSignature::NoMatch);
+    }
+
+    #[test]
+    fn match_names_test() {
+        assert_eq!(
+            match_names("Hellos01e02mov", "Hellos01e02WebSub"),
+            MatchSignature::Match
+        );
+        assert_eq!(
+            match_names("Hellos01e02mov", "Hellos01 e02mov"),
+            MatchSignature::Match
+        );
+        assert_eq!(
+            match_names("some.video.file.S04 E01.mp4", "some.video.file.S04E01.srt"),
+            MatchSignature::Match
+        );
+        assert_eq!(
+            match_names("Show Season 1 Episode 2.mkv", "Show.S01E02.srt"),
+            MatchSignature::Match
+        );
+        assert_eq!(
+            match_names("Show [1x02].mkv", "Show (S01E02).srt"),
+            MatchSignature::Match
+        );
+        assert_eq!(
+            match_names("show.s01e02a.mkv", "show.s01e02b.srt"),
+            MatchSignature::NoMatch
+        );
+        assert_eq!(
+            match_names("Show.S01E02.mkv", "Show.mkv"),
+            MatchSignature::NoMatch
+        );
+    }
+
+    #[test]
+    fn episode_name_signature_check_numeric_code_test() {
+        let options = SignatureOptions {
+            numeric_code: true,
+            ..Default::default()
+        };
+        let check = |first_name, second_name, options: &SignatureOptions| {
+            super::episode_name_signature_check(
+                OsStr::new(first_name),
+                OsStr::new(second_name),
+                options,
+            )
+        };
+
+        assert_eq!(
+            check("Show.0102.mkv", "Show.S01E02.srt", &options),
+            MatchSignature::Match
+        );
+        assert_eq!(
+            check("Show.0102.mkv", "Show.S01E03.srt", &options),
+            MatchSignature::NoMatch
+        );
+        assert_eq!(
+            check("Show.0102.1080p.mkv", "Show 0102.srt", &options),
+            MatchSignature::Match
+        );
+        // Codes are only read with the option, as years look the same
+        assert_eq!(
+            check(
+                "Show.0102.mkv",
+                "Show.S01E02.srt",
+                &SignatureOptions::default()
+            ),
+            MatchSignature::NoMatch
+        );
+        assert_eq!(
+            check(
+                "Show.1999.mkv",
+                "Show.S19E99.srt",
+                &SignatureOptions::default()
+            ),
+            MatchSignature::NoMatch
+        );
+        // Longer numbers and numbers glued to letters are not codes
+        assert_eq!(get_numeric_code_signature("Show.01020.mkv"), None);
+        assert_eq!(get_numeric_code_signature("Show.x0102.mkv"), None);
+    }
+
+    #[test]
+    fn get_signature_end_test() {
+        assert_eq!(get_signature_end("Show.S01E02.The.Title"), Some(11));
+        assert_eq!(get_signature_end("Show.S01E02a.The.Title"), Some(12));
+        assert_eq!(get_signature_end("Show 1x02 The Title"), Some(9));
+        assert_eq!(get_signature_end("Show"), None);
+    }
+
+    #[test]
+    fn compact_signature_test() {
+        assert_eq!(
+            compact_signature("Show.S01 E02.The.Title").as_deref(),
+            Some("Show.S01E02.The.Title")
+        );
+        assert_eq!(
+            compact_signature("Show s01 - e02").as_deref(),
+            Some("Show s01e02")
+        );
+        assert_eq!(compact_signature("Show.S01E02"), None);
+        assert_eq!(compact_signature("Show Season 1 Episode 2"), None);
+        assert_eq!(compact_signature("Show 1x02"), None);
+    }
+
+    #[test]
+    fn byte_range_test() {
+        let name = "Show.S01E02";
+        assert_eq!(byte_range(name, &name[5..8]), Some(5..8));
+        assert_eq!(byte_range(name, &name[11..]), Some(11..11));
+    }
+
+    #[test]
+    #[should_panic(expected = "is not a slice of")]
+    fn byte_range_not_a_slice_test() {
+        let name = "Show.S01E02".to_string();
+        byte_range(&name, "S01");
+    }
+
+    #[test]
+    fn titles_differ_test() {
+        let titles_differ = |first_name, second_name| {
+            titles_differ(OsStr::new(first_name), OsStr::new(second_name), false)
+        };
+
+        assert!(titles_differ(
+            "Breaking.Bad.S01E01.mkv",
+            "Better Call Saul s01e01.srt"
+        ));
+        assert!(titles_differ(
+            "The.Office.S01E01.mkv",
+            "The Wire S01E01.srt"
+        ));
+        assert!(!titles_differ(
+            "Breaking.Bad.S01E01.mkv",
+            "breaking bad s01e01 web.srt"
+        ));
+        assert!(!titles_differ("Show (2019) S01E02.mkv", "Show.S01E02.srt"));
+        // Release groups leading the names are not part of the titles
+        assert!(!titles_differ("[Group] Show.S01E02.mkv", "Show.S01E02.srt"));
+        assert!(!titles_differ(
+            "[SubsPlease] Show - 27 [1080p].mkv",
+            "Show - 27.srt"
+        ));
+        assert!(!titles_differ(
+            "(Group) [Raws] Show S01E02.mkv",
+            "Show.S01E02.srt"
+        ));
+        assert!(titles_differ("[Group] Show.S01E02.mkv", "Other.S01E02.srt"));
+        assert!(!titles_differ(
+            "Show Season 1 Episode 2.mkv",
+            "Show.S01E02.srt"
+        ));
+        assert!(!titles_differ(
+            "The.Office.US.S01E01.mkv",
+            "The Office S01E01.srt"
+        ));
+        // Names without a title can't be told apart
+        assert!(!titles_differ("Show.S01E02.mkv", "S01E02.srt"));
+        assert!(!titles_differ(
+            "Show.S01E02.mkv",
+            "Subs/Show.S01E02/2_English.srt"
+        ));
+    }
+
+    #[test]
+    fn show_title_test() {
+        let show_title = |file_name| show_title(OsStr::new(file_name));
+
+        assert_eq!(
+            show_title("Breaking.Bad.S01E02.mkv").as_deref(),
+            Some("Breaking Bad")
+        );
+        assert_eq!(
+            show_title("the office (2005) s01e01 web.srt").as_deref(),
+            Some("the office")
+        );
+        assert_eq!(
+            show_title("[Group] Breaking.Bad.S01E02.mkv").as_deref(),
+            Some("Breaking Bad")
+        );
+        assert_eq!(show_title("S01E02.srt"), None);
+    }
+
+    #[test]
+    fn unicode_digits_test() {
+        assert_eq!(
+            get_signature(OsStr::new("Show.S\u{0660}\u{0661}E\u{0660}\u{0662}.mkv")),
+            Signature::parse("S01E02")
+        );
+        assert_eq!(
+            match_names("مسلسل S٠١E٠٢.mkv", "Show S01E02.srt"),
+            MatchSignature::Match
+        );
+        assert_eq!(
+            match_names("مسلسل S٠١E٠٢.mkv", "Show S01E03.srt"),
+            MatchSignature::NoMatch
+        );
+        assert_eq!(
+            get_absolute_episode(OsStr::new("Show - ۲۷.srt"), &SignatureOptions::default()),
+            Some(27)
+        );
+        assert_eq!(to_ascii_digits("Show S01E02"), Cow::Borrowed("Show S01E02"));
+        assert_eq!(to_ascii_digits("S\u{FF10}\u{FF11}"), "S01");
+    }
+
+    #[test]
+    fn duplicate_download_marker_test() {
+        assert_eq!(
+            match_names("Show.S01E02.mkv", "Show.s01e02 (1).srt"),
+            MatchSignature::Match
+        );
+        assert_eq!(
+            match_names("Show.S01E03.mkv", "Show.s01e02 (3).srt"),
+            MatchSignature::NoMatch
+        );
+        assert_eq!(
+            get_absolute_episode(
+                OsStr::new("[Group] Show - 27 (2).srt"),
+                &SignatureOptions::default()
+            ),
+            Some(27)
+        );
+    }
+
+    #[test]
+    fn default_season_test() {
+        let options = SignatureOptions {
+            default_season: Some(1),
+            ..Default::default()
+        };
+
+        assert_eq!(get_signature(OsStr::new("Show.E02.mkv")), None);
+        assert_eq!(
+            get_signature_with_options(OsStr::new("Show.E02.mkv"), &options),
+            Signature::parse("S01E02")
+        );
+        // Names having a season keep it
+        assert_eq!(
+            get_signature_with_options(OsStr::new("Show.S03E02.mkv"), &options),
+            Signature::parse("S03E02")
+        );
+        assert!(super::episode_name_signature_check(
+            OsStr::new("Show.E02.mkv"),
+            OsStr::new("show e02 web.srt"),
+            &options
+        )
+        .is_match());
+        assert!(!super::episode_name_signature_check(
+            OsStr::new("Show.E02.mkv"),
+            OsStr::new("show e03 web.srt"),
+            &options
+        )
+        .is_match());
+
+        assert_eq!(
+            add_default_season("Show.E02.The.Title", 1).as_deref(),
+            Some("Show.S01E02.The.Title")
+        );
+        assert_eq!(
+            add_default_season("show e02", 2).as_deref(),
+            Some("show s02e02")
+        );
+        assert_eq!(add_default_season("Show.S01E02", 2), None);
+        assert_eq!(add_default_season("Show - 02", 2), None);
+    }
+
+    #[test]
+    fn three_digit_episodes_test() {
+        for name in [
+            "Show.s01e120.mkv",
+            "Show 1x120.mkv",
+            "Show Season 1 Episode 120.mkv",
+        ] {
+            assert_eq!(get_signature(OsStr::new(name)), Signature::parse("S01E120"));
+        }
+        assert_eq!(
+            match_names("Show.s01e120.mkv", "show s01e120 web.srt"),
+            MatchSignature::Match
+        );
+        // Neither number is cut short to match the other
+        assert_eq!(
+            match_names("Show.s01e120.mkv", "show s01e12.srt"),
+            MatchSignature::NoMatch
+        );
+        assert_eq!(
+            match_names("Show.s01e1200.mkv", "show s01e120.srt"),
+            MatchSignature::NoMatch
+        );
+        assert_eq!(get_signature_end("Show.S01E120a.The.Title"), Some(13));
+        assert_eq!(
+            inspect_signature(OsStr::new("Show.S01E120")).episode,
+            Some(SignaturePart {
+                text: "120".to_string(),
+                range: 9..12
+            })
+        );
+        assert_eq!(
+            get_absolute_episode(OsStr::new("Show - 120.srt"), &SignatureOptions::default()),
+            Some(120)
+        );
+    }
+
+    #[test]
+    fn split_signature_positions_test() {
+        let name = OsStr::new("s01.Show.e02.The.Title.mkv");
+
+        assert_eq!(
+            get_signature(name),
+            Some(Signature {
+                season: 1,
+                episode: 2,
+                episode_suffix: None
+            })
+        );
+        assert_eq!(
+            match_names("s01.Show.e02.The.Title.mkv", "Show S01E02.srt"),
+            MatchSignature::Match
+        );
+        assert_eq!(
+            match_names("S01.Show.E02.mkv", "Show S01E03.srt"),
+            MatchSignature::NoMatch
+        );
+        assert_eq!(show_title(name).as_deref(), Some("Show"));
+        assert!(titles_differ(
+            name,
+            OsStr::new("Other Show S01E02.srt"),
+            false
+        ));
+        assert!(!titles_differ(name, OsStr::new("show s01e02.srt"), false));
+        assert_eq!(get_signature_end("s01.Show.e02.The.Title"), Some(12));
+        assert_eq!(compact_signature("s01.Show.e02.The.Title"), None);
+    }
+
+    #[test]
+    fn get_title_and_year_test() {
+        assert_eq!(
+            get_title_and_year("Grey's.Anatomy.2005.S01E02"),
+            Some(("Grey's.Anatomy", Some(2005)))
+        );
+        assert_eq!(
+            get_title_and_year("[Group] Show.2019.S01E02"),
+            Some(("Show", Some(2019)))
+        );
+        assert_eq!(
+            get_title_and_year("Show Name - s01e02 - Title"),
+            Some(("Show Name", None))
+        );
+        assert_eq!(
+            get_title_and_year("Show (2019) S01E02"),
+            Some(("Show", Some(2019)))
+        );
+        assert_eq!(get_title_and_year("S01E02"), None);
+    }
+
+    #[test]
+    fn titles_differ_case_test() {
+        let first_name = OsStr::new("Breaking.Bad.S01E01.mkv");
+        let second_name = OsStr::new("breaking.bad.s01e01.srt");
+
+        assert!(!titles_differ(first_name, second_name, false));
+        assert!(titles_differ(first_name, second_name, true));
+        assert!(!titles_differ(
+            first_name,
+            OsStr::new("Breaking Bad S01E01.srt"),
+            true
+        ));
+    }
+
+    #[test]
+    fn signature_inspection_error_test() {
+        let error = inspect_signature(OsStr::new("Show.S01.Part2.mkv")).error();
+        assert_eq!(error, Some(SignatureError::EpisodeMissing { season: 6..8 }));
+        assert_eq!(
+            error.unwrap().to_string(),
+            "A season has been found at bytes 6..8 but no episode"
+        );
+
+        assert_eq!(
+            inspect_signature(OsStr::new("Show E05.mkv")).error(),
+            Some(SignatureError::SeasonMissing { episode: 6..8 })
+        );
+        assert_eq!(
+            inspect_signature(OsStr::new("Show.mkv")).error(),
+            Some(SignatureError::NotFound)
+        );
+        assert_eq!(
+            inspect_signature(OsStr::new("Show.S01E02.mkv")).error(),
+            None
+        );
+    }
+
+    #[test]
+    fn same_episode_number_test() {
+        assert!(same_episode_number("Show.S01E05.mkv", "Show.S02E05.srt"));
+        assert!(same_episode_number("Show.S01E05.mkv", "Show E05.srt"));
+        assert!(same_episode_number(
+            "Show 1x05.mkv",
+            "Season 3/Show Episode 5.srt"
+        ));
+        assert!(!same_episode_number("Show.S01E05.mkv", "Show.S01E06.srt"));
+        assert!(!same_episode_number("Show.S01E05.mkv", "Show.srt"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn episode_name_signature_check_non_utf8_name_test() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let name_1 = OsStr::from_bytes(b"Sh\xffow/Show\xfe\xff.S01E02.mkv");
+        let name_2 = OsStr::new("show s01e02.srt");
+
+        assert_eq!(
+            episode_name_signature_check(name_1, name_2),
+            MatchSignature::Match
+        );
+        assert_eq!(get_signature(name_1).unwrap().to_string(), "S01E02");
+    }
+
+    #[test]
+    fn get_match_key_test() {
+        let options = SignatureOptions {
+            episode_offset: 1,
+            hash_episodes: true,
+            ..Default::default()
+        };
+        let match_key = |name, offset| get_match_key(OsStr::new(name), &options, offset);
+
+        assert_eq!(
+            match_key("Show.S01E02.srt", false),
+            Some(MatchKey::Signature(Signature::parse("S01E02").unwrap()))
+        );
+        assert_eq!(
+            match_key("Show.S01E02.srt", true),
+            Some(MatchKey::Signature(Signature::parse("S01E03").unwrap()))
+        );
+        assert_eq!(
+            match_key("Show #102.srt", true),
+            Some(MatchKey::AbsoluteEpisode(103))
+        );
+        assert_eq!(match_key("Show.srt", false), None);
+        assert_eq!(
+            get_match_key(
+                OsStr::new("Show #102.srt"),
+                &SignatureOptions::default(),
+                false
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn episode_name_signature_check_hash_episode_test() {
+        let options = SignatureOptions {
+            hash_episodes: true,
+            ..Default::default()
+        };
+        let check = |first_name, second_name| {
+            super::episode_name_signature_check(
+                OsStr::new(first_name),
+                OsStr::new(second_name),
+                &options,
+            )
+        };
+
+        assert_eq!(
+            check("Show #102.mkv", "Show #102.srt"),
+            MatchSignature::Match
+        );
+        assert_eq!(
+            check("Show #102.mkv", "Show E102.srt"),
+            MatchSignature::Match
+        );
+        assert_eq!(
+            check("Show #102.mkv", "Show #103.srt"),
+            MatchSignature::NoMatch
+        );
+        assert_eq!(
+            check("Show #1080p.mkv", "Show #1080p.srt"),
+            MatchSignature::NoMatch
+        );
+        // "#" numbers are opt-in
+        assert_eq!(
+            episode_name_signature_check(OsStr::new("Show #102.mkv"), OsStr::new("Show #102.srt")),
+            MatchSignature::NoMatch
+        );
+    }
+
+    #[test]
+    fn get_absolute_episode_anime_test() {
+        let options = SignatureOptions::default();
+        let absolute_episode = |name| get_absolute_episode(OsStr::new(name), &options);
+
+        assert_eq!(
+            absolute_episode("[Group] Show - 02 [1080p][ABCD1234].mkv"),
+            Some(2)
+        );
+        // The CRC hash could be mistaken for an episode signature
+        assert_eq!(
+            absolute_episode("[Group] Show - 27 [1080p][5E12AB34].mkv"),
+            Some(27)
+        );
+        assert_eq!(absolute_episode("[Group] Show - 02v2 [720p].mkv"), Some(2));
+        assert_eq!(absolute_episode("[Group] Show - 02.srt"), Some(2));
+        assert_eq!(absolute_episode("Show - The Movie [1080p].mkv"), None);
+        assert_eq!(absolute_episode("Show - 2nd Season [1080p].mkv"), None);
+
+        let episode_map = HashMap::from([(27, Signature::parse("S02E05").unwrap())]);
+        assert_eq!(
+            super::episode_name_signature_check(
+                OsStr::new("Show.S02E05.mkv"),
+                OsStr::new("[Group] Show - 27 [1080p][5E12AB34].srt"),
+                &SignatureOptions {
+                    episode_map: Some(&episode_map),
+                    ..Default::default()
+                }
+            ),
+            MatchSignature::Match
+        );
+    }
 }
@@ -0,0 +1,97 @@
+use anyhow::{bail, Result};
+use std::io::{BufRead, Write};
+use std::path;
+
+/// A planned rename of a subtitle file, from its current path to its new path
+#[derive(Debug, PartialEq)]
+pub struct PlannedRename {
+    pub old: path::PathBuf,
+    pub new: path::PathBuf,
+}
+
+/// Writes the planned renames as "old<TAB>new" lines, one rename per line
+///
+/// # Errors
+/// Returns an error when a path can't be written on a single line, that is when it is not
+/// valid UTF-8 or contains a tab or a newline, or when writing fails
+pub fn write_plan(writer: &mut impl Write, planned_renames: &[PlannedRename]) -> Result<()> {
+    for planned_rename in planned_renames {
+        let old = plan_line_field(&planned_rename.old)?;
+        let new = plan_line_field(&planned_rename.new)?;
+        writeln!(writer, "{}\t{}", old, new)?;
+    }
+
+    Ok(())
+}
+
+/// Reads the planned renames written by [`write_plan`], possibly edited by hand.
+/// Empty lines are ignored
+///
+/// # Errors
+/// Returns an error when a line doesn't have exactly two tab separated paths, or when
+/// reading fails
+pub fn read_plan(reader: impl BufRead) -> Result<Vec<PlannedRename>> {
+    let mut planned_renames = Vec::new();
+
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let mut fields = line.split('\t');
+        match (fields.next(), fields.next(), fields.next()) {
+            (Some(old), Some(new), None) if !old.is_empty() && !new.is_empty() => planned_renames
+                .push(PlannedRename {
+                    old: old.into(),
+                    new: new.into(),
+                }),
+            _ => bail!(
+                "Line {} of the plan file is not in the 'old<TAB>new' format",
+                index + 1
+            ),
+        }
+    }
+
+    Ok(planned_renames)
+}
+
+/// Returns the path as a plan file field
+fn plan_line_field(path: &path::Path) -> Result<&str> {
+    match path.to_str() {
+        Some(field) if !field.contains(['\t', '\n', '\r']) => Ok(field),
+        _ => bail!(
+            "'{}' can't be written to the plan file",
+            path.to_string_lossy()
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{read_plan, write_plan, PlannedRename};
+
+    #[test]
+    fn plan_round_trip_test() {
+        let planned_renames = vec![
+            PlannedRename {
+                old: "dir/show s01e01 web.srt".into(),
+                new: "dir/Show.S01E01.srt".into(),
+            },
+            PlannedRename {
+                old: "dir/show s01e02 web.srt".into(),
+                new: "dir/Show.S01E02.srt".into(),
+            },
+        ];
+
+        let mut plan = Vec::new();
+        write_plan(&mut plan, &planned_renames).unwrap();
+
+        assert_eq!(
+            String::from_utf8_lossy(&plan),
+            "dir/show s01e01 web.srt\tdir/Show.S01E01.srt\ndir/show s01e02 web.srt\tdir/Show.S01E02.srt\n"
+        );
+        assert_eq!(read_plan(plan.as_slice()).unwrap(), planned_renames);
+        assert!(read_plan("only-one-path.srt\n".as_bytes()).is_err());
+    }
+}
@@ -0,0 +1,735 @@
+use clap::ValueEnum;
+use colored::*;
+use serde::Serialize;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+use std::path;
+#[cfg(feature = "zip")]
+use sub_auto_rename::ZippedSubtitleFile;
+use sub_auto_rename::{MovieFile, SubtitleFile};
+
+/// The format in which the reports of a run are printed
+#[derive(Debug, Default, Clone, Copy, PartialEq, ValueEnum)]
+pub enum OutputFormat {
+    /// Colored output meant to be read by people
+    #[default]
+    Human,
+    /// JSON output meant to be consumed by other programs
+    Json,
+}
+
+/// Statistics of a single run of the program
+#[derive(Debug, Default, Serialize)]
+pub struct RunStats {
+    /// Subtitle files that have been renamed
+    pub renamed: usize,
+    /// Subtitle files that were skipped as they have already been renamed or as the path they
+    /// would be renamed to is taken
+    pub skipped: usize,
+    /// Subtitle files that have not been matched to any movie file
+    pub unmatched_subs: usize,
+    /// Movie files that have not been matched to any subtitle file
+    pub unmatched_movies: usize,
+    /// Errors encountered when renaming subtitle files
+    pub errors: usize,
+}
+
+impl RunStats {
+    /// Counts the statistics of a run from the records of its files
+    pub fn from_records(records: &[RenameRecord]) -> Self {
+        let summary = RunSummary::new(false, records);
+        Self {
+            renamed: summary.renamed,
+            skipped: summary.skipped,
+            unmatched_subs: summary.unmatched_subs,
+            unmatched_movies: summary.unmatched_movies,
+            errors: summary.failed,
+        }
+    }
+
+    /// Returns the statistics as a single JSON object
+    pub fn to_json(&self) -> String {
+        // SAFETY: the struct only contains numbers, serializing it can't fail
+        serde_json::to_string(self).unwrap()
+    }
+
+    /// Prints the statistics in the given output format
+    pub fn print(&self, format: OutputFormat) {
+        match format {
+            OutputFormat::Human => {
+                println!("\n-------------- STATISTICS --------------");
+                println!("Renamed          : {}", format!("{}", self.renamed).green());
+                println!(
+                    "Skipped          : {}",
+                    format!("{}", self.skipped).yellow()
+                );
+                println!(
+                    "Unmatched subs   : {}",
+                    format!("{}", self.unmatched_subs).red()
+                );
+                println!(
+                    "Unmatched movies : {}",
+                    format!("{}", self.unmatched_movies).red()
+                );
+                println!("Errors           : {}", format!("{}", self.errors).red());
+            }
+            OutputFormat::Json => println!("{}", self.to_json()),
+        }
+    }
+}
+
+/// A subtitle file the reports of a run are about, be it a plain or a zipped subtitle file
+pub trait RecordedSubtitle: std::fmt::Display {
+    /// Returns the path of the file
+    fn path(&self) -> &path::Path;
+}
+
+impl RecordedSubtitle for SubtitleFile {
+    fn path(&self) -> &path::Path {
+        SubtitleFile::path(self)
+    }
+}
+
+#[cfg(feature = "zip")]
+impl RecordedSubtitle for ZippedSubtitleFile {
+    fn path(&self) -> &path::Path {
+        ZippedSubtitleFile::path(self)
+    }
+}
+
+/// A movie file and the subtitle file matched to it
+#[derive(Debug, Serialize)]
+pub struct MatchedPair {
+    movie: String,
+    subtitle: String,
+}
+
+impl MatchedPair {
+    /// Constructs a MatchedPair from the matched movie file and subtitle file
+    pub fn new(movie_file: &MovieFile, subtitle_file: &impl RecordedSubtitle) -> Self {
+        Self {
+            movie: movie_file.to_string(),
+            subtitle: subtitle_file.to_string(),
+        }
+    }
+}
+
+impl std::fmt::Display for MatchedPair {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} <-> {}", self.movie, self.subtitle)
+    }
+}
+
+/// Prints the matched pairs in the given output format
+pub fn print_matched_pairs(matched_pairs: &[MatchedPair], format: OutputFormat) {
+    match format {
+        OutputFormat::Human => {
+            for matched_pair in matched_pairs {
+                println!("{}", matched_pair);
+            }
+            println!(
+                "\n{}",
+                format!("Matched pairs: {}", matched_pairs.len()).blue()
+            );
+        }
+        OutputFormat::Json => {
+            // SAFETY: the pairs only contain strings, serializing them can't fail
+            println!("{}", serde_json::to_string(matched_pairs).unwrap())
+        }
+    }
+}
+
+/// What happened to a movie file or a subtitle file during a run
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RenameStatus {
+    /// The subtitle file has been renamed after the movie file
+    Renamed,
+    /// The subtitle file would be renamed after the movie file, were it not a dry run
+    WouldRename,
+    /// The subtitle file has been left alone, as it has already been renamed after the movie
+    /// file or as the path it would be renamed to is taken
+    Skipped,
+    /// Renaming the subtitle file after the movie file failed
+    Failed,
+    /// The file has not been matched to any other file
+    Unmatched,
+}
+
+impl RenameStatus {
+    /// Returns the status the way it is written in reports
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Renamed => "renamed",
+            Self::WouldRename => "would_rename",
+            Self::Skipped => "skipped",
+            Self::Failed => "failed",
+            Self::Unmatched => "unmatched",
+        }
+    }
+}
+
+/// The outcome of a single movie file or subtitle file of a run, fields not applying to the
+/// outcome, like the subtitle of an unmatched movie file, are left empty
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct RenameRecord {
+    pub movie: String,
+    pub subtitle: String,
+    pub old_name: String,
+    pub new_name: String,
+    pub status: Option<RenameStatus>,
+}
+
+impl RenameRecord {
+    /// Constructs a RenameRecord of a subtitle file and the path it has been, or would have
+    /// been, renamed to
+    pub fn new(
+        movie_file: Option<&MovieFile>,
+        subtitle_file: &impl RecordedSubtitle,
+        new_path: Option<&path::Path>,
+        status: RenameStatus,
+    ) -> Self {
+        let file_name = |path: &path::Path| {
+            path.file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .into_owned()
+        };
+
+        Self {
+            movie: movie_file.map(ToString::to_string).unwrap_or_default(),
+            subtitle: subtitle_file.to_string(),
+            old_name: file_name(subtitle_file.path()),
+            new_name: new_path.map(file_name).unwrap_or_default(),
+            status: Some(status),
+        }
+    }
+
+    /// Returns whether the record is a problem, that is an unmatched movie file or subtitle file
+    /// or a failed rename
+    pub fn is_problem(&self) -> bool {
+        matches!(
+            self.status,
+            Some(RenameStatus::Unmatched | RenameStatus::Failed)
+        )
+    }
+
+    /// Constructs a RenameRecord of a movie file that has not been matched to any subtitle file
+    pub fn unmatched_movie(movie_file: &MovieFile) -> Self {
+        Self {
+            movie: movie_file.to_string(),
+            status: Some(RenameStatus::Unmatched),
+            ..Default::default()
+        }
+    }
+}
+
+/// The records of all the files of a run, telling whether the run was a dry run
+#[derive(Debug, Serialize)]
+pub struct RunResults<'a> {
+    pub dry_run: bool,
+    pub results: &'a [RenameRecord],
+}
+
+impl RunResults<'_> {
+    /// Returns the results as a single JSON object
+    pub fn to_json(&self) -> String {
+        // SAFETY: the records only contain strings and unit variants, serializing them can't fail
+        serde_json::to_string(self).unwrap()
+    }
+}
+
+/// The number of records of each status of a run, unmatched subtitle files and movie files
+/// being counted apart
+#[derive(Debug, Serialize)]
+pub struct RunSummary {
+    pub dry_run: bool,
+    pub renamed: usize,
+    pub would_rename: usize,
+    pub skipped: usize,
+    pub failed: usize,
+    pub unmatched_subs: usize,
+    pub unmatched_movies: usize,
+}
+
+impl RunSummary {
+    /// Counts the records of a run
+    pub fn new(dry_run: bool, records: &[RenameRecord]) -> Self {
+        let count = |status| {
+            records
+                .iter()
+                .filter(|record| record.status == Some(status))
+                .count()
+        };
+        let unmatched_movies = records
+            .iter()
+            .filter(|record| record.status == Some(RenameStatus::Unmatched))
+            .filter(|record| record.subtitle.is_empty())
+            .count();
+
+        Self {
+            dry_run,
+            renamed: count(RenameStatus::Renamed),
+            would_rename: count(RenameStatus::WouldRename),
+            skipped: count(RenameStatus::Skipped),
+            failed: count(RenameStatus::Failed),
+            unmatched_subs: count(RenameStatus::Unmatched) - unmatched_movies,
+            unmatched_movies,
+        }
+    }
+
+    /// Returns the summary as a single JSON object
+    pub fn to_json(&self) -> String {
+        // SAFETY: the struct only contains booleans and numbers, serializing it can't fail
+        serde_json::to_string(self).unwrap()
+    }
+}
+
+/// Writes a summary block of the counts, telling how many subtitle files would be renamed
+/// instead of how many have been renamed for dry runs
+pub fn write_summary(writer: &mut impl Write, summary: &RunSummary) -> io::Result<()> {
+    writeln!(writer, "-------------- SUMMARY --------------")?;
+    if summary.dry_run {
+        writeln!(writer, "Would rename     : {}", summary.would_rename)?;
+    } else {
+        writeln!(writer, "Renamed          : {}", summary.renamed)?;
+    }
+    writeln!(writer, "Skipped          : {}", summary.skipped)?;
+    writeln!(writer, "Failed           : {}", summary.failed)?;
+    writeln!(writer, "Unmatched subs   : {}", summary.unmatched_subs)?;
+    writeln!(writer, "Unmatched movies : {}", summary.unmatched_movies)
+}
+
+/// Writes a line for each record of a problem, that is each unmatched movie file, unmatched
+/// subtitle file and failed rename, leaving out the files that went fine
+pub fn write_problems(writer: &mut impl Write, records: &[RenameRecord]) -> io::Result<()> {
+    for record in records {
+        match record.status {
+            Some(RenameStatus::Unmatched) if record.subtitle.is_empty() => {
+                writeln!(writer, "Unmatched movie    : {}", record.movie)?
+            }
+            Some(RenameStatus::Unmatched) => {
+                writeln!(writer, "Unmatched subtitle : {}", record.subtitle)?
+            }
+            Some(RenameStatus::Failed) => {
+                writeln!(writer, "Failed             : {}", record.subtitle)?
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes the records grouped by the show title of their files, like "Breaking Bad" for
+/// "Breaking.Bad.S01E02.mkv", for directories holding several shows. The title is taken from
+/// the movie file, or from the subtitle file of unmatched subtitle files, titles differing in
+/// case only being the same show. Records without a title are grouped last
+pub fn write_groups_by_show(writer: &mut impl Write, records: &[RenameRecord]) -> io::Result<()> {
+    let mut groups: BTreeMap<String, (String, Vec<&RenameRecord>)> = BTreeMap::new();
+    let mut untitled_records = Vec::new();
+
+    for record in records {
+        let file = if record.movie.is_empty() {
+            &record.subtitle
+        } else {
+            &record.movie
+        };
+        let title = path::Path::new(file)
+            .file_name()
+            .and_then(sub_auto_rename::show_title);
+
+        match title {
+            Some(title) => groups
+                .entry(title.to_lowercase())
+                .or_insert_with(|| (title, Vec::new()))
+                .1
+                .push(record),
+            None => untitled_records.push(record),
+        }
+    }
+
+    let groups = groups.into_values();
+    let untitled_group =
+        (!untitled_records.is_empty()).then(|| ("Unknown show".to_string(), untitled_records));
+    for (title, records) in groups.chain(untitled_group) {
+        writeln!(writer, ":: {} ({} files)", title, records.len())?;
+        for record in records {
+            match record.status {
+                Some(status @ (RenameStatus::Renamed | RenameStatus::WouldRename)) => writeln!(
+                    writer,
+                    "- {} : {} -> {}",
+                    status.as_str(),
+                    record.old_name,
+                    record.new_name
+                )?,
+                Some(RenameStatus::Unmatched) if record.subtitle.is_empty() => {
+                    writeln!(writer, "- unmatched : {}", record.movie)?
+                }
+                Some(status) => writeln!(writer, "- {} : {}", status.as_str(), record.subtitle)?,
+                None => {}
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes the record as a single line of JSON, the way the rename log of JSON Lines is written
+pub fn write_json_line(writer: &mut impl Write, record: &RenameRecord) -> io::Result<()> {
+    // SAFETY: the record only contains strings and unit variants, serializing it can't fail
+    writeln!(writer, "{}", serde_json::to_string(record).unwrap())
+}
+
+/// Writes the records as CSV with a "movie,subtitle,old_name,new_name,status" header
+pub fn write_csv_report(writer: &mut impl Write, records: &[RenameRecord]) -> io::Result<()> {
+    writeln!(writer, "movie,subtitle,old_name,new_name,status")?;
+
+    for record in records {
+        writeln!(
+            writer,
+            "{},{},{},{},{}",
+            csv_field(&record.movie),
+            csv_field(&record.subtitle),
+            csv_field(&record.old_name),
+            csv_field(&record.new_name),
+            record
+                .status
+                .map(|status| status.as_str())
+                .unwrap_or_default()
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Returns the value as a CSV field, quoting it when it contains commas, quotes or line breaks
+fn csv_field(value: &str) -> Cow<'_, str> {
+    if value.contains([',', '"', '\n', '\r']) {
+        Cow::Owned(format!("\"{}\"", value.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        write_csv_report, write_groups_by_show, write_json_line, write_problems, write_summary,
+        MatchedPair, RenameRecord, RenameStatus, RunResults, RunStats, RunSummary,
+    };
+    use std::path;
+    use sub_auto_rename::{MovieFile, SubtitleFile};
+
+    #[test]
+    fn run_stats_json_shape_test() {
+        let stats = RunStats {
+            renamed: 3,
+            skipped: 1,
+            unmatched_subs: 2,
+            unmatched_movies: 0,
+            errors: 1,
+        };
+
+        assert_eq!(
+            stats.to_json(),
+            r#"{"renamed":3,"skipped":1,"unmatched_subs":2,"unmatched_movies":0,"errors":1}"#
+        );
+    }
+
+    #[test]
+    fn run_stats_from_records_test() {
+        let movie_file = MovieFile::new(path::PathBuf::from("show.s01e02.mkv"), None).unwrap();
+        let subtitle_file =
+            SubtitleFile::try_from(path::PathBuf::from("show s01e02 web.srt")).unwrap();
+        let records = [RenameRecord::new(
+            Some(&movie_file),
+            &subtitle_file,
+            Some(path::Path::new("show.s01e02.srt")),
+            RenameStatus::Failed,
+        )];
+
+        // The files of a failed rename are not unmatched
+        assert_eq!(
+            RunStats::from_records(&records).to_json(),
+            r#"{"renamed":0,"skipped":0,"unmatched_subs":0,"unmatched_movies":0,"errors":1}"#
+        );
+
+        let mut report = Vec::new();
+        write_csv_report(&mut report, &records).unwrap();
+        assert_eq!(
+            String::from_utf8(report).unwrap(),
+            "movie,subtitle,old_name,new_name,status\n\
+             show.s01e02.mkv,show s01e02 web.srt,show s01e02 web.srt,show.s01e02.srt,failed\n"
+        );
+        let mut summary = Vec::new();
+        write_summary(&mut summary, &RunSummary::new(false, &records)).unwrap();
+        assert!(String::from_utf8(summary)
+            .unwrap()
+            .contains("Failed           : 1\nUnmatched subs   : 0\nUnmatched movies : 0\n"));
+    }
+
+    #[test]
+    fn matched_pair_output_test() {
+        let movie_file = MovieFile::new(path::PathBuf::from("show.s01e02.mkv"), None).unwrap();
+        let subtitle_file =
+            SubtitleFile::try_from(path::PathBuf::from("show s01e02 web.srt")).unwrap();
+        let matched_pair = MatchedPair::new(&movie_file, &subtitle_file);
+
+        assert_eq!(
+            matched_pair.to_string(),
+            "show.s01e02.mkv <-> show s01e02 web.srt"
+        );
+        assert_eq!(
+            serde_json::to_string(&matched_pair).unwrap(),
+            r#"{"movie":"show.s01e02.mkv","subtitle":"show s01e02 web.srt"}"#
+        );
+    }
+
+    #[test]
+    fn csv_report_test() {
+        let movie_file = MovieFile::new(path::PathBuf::from("show.s01e02.mkv"), None).unwrap();
+        let subtitle_file =
+            SubtitleFile::try_from(path::PathBuf::from("show, \"web\" s01e02.srt")).unwrap();
+        let unmatched_movie_file =
+            MovieFile::new(path::PathBuf::from("show.s01e03.mkv"), None).unwrap();
+        let records = [
+            RenameRecord::new(
+                Some(&movie_file),
+                &subtitle_file,
+                Some(path::Path::new("show.s01e02.srt")),
+                RenameStatus::Renamed,
+            ),
+            RenameRecord::unmatched_movie(&unmatched_movie_file),
+        ];
+
+        let mut report = Vec::new();
+        write_csv_report(&mut report, &records).unwrap();
+
+        assert_eq!(
+            String::from_utf8(report).unwrap(),
+            "movie,subtitle,old_name,new_name,status\n\
+             show.s01e02.mkv,\"show, \"\"web\"\" s01e02.srt\",\"show, \"\"web\"\" s01e02.srt\",show.s01e02.srt,renamed\n\
+             show.s01e03.mkv,,,,unmatched\n"
+        );
+    }
+
+    #[test]
+    fn dry_run_results_json_test() {
+        let movie_file = MovieFile::new(path::PathBuf::from("show.s01e02.mkv"), None).unwrap();
+        let subtitle_file =
+            SubtitleFile::try_from(path::PathBuf::from("show s01e02 web.srt")).unwrap();
+        let records = [RenameRecord::new(
+            Some(&movie_file),
+            &subtitle_file,
+            Some(path::Path::new("show.s01e02.srt")),
+            RenameStatus::WouldRename,
+        )];
+
+        assert_eq!(
+            RunResults {
+                dry_run: true,
+                results: &records
+            }
+            .to_json(),
+            r#"{"dry_run":true,"results":[{"movie":"show.s01e02.mkv","subtitle":"show s01e02 web.srt","old_name":"show s01e02 web.srt","new_name":"show.s01e02.srt","status":"would_rename"}]}"#
+        );
+    }
+
+    #[test]
+    fn json_lines_test() {
+        let movie_file = MovieFile::new(path::PathBuf::from("show.s01e02.mkv"), None).unwrap();
+        let subtitle_file =
+            SubtitleFile::try_from(path::PathBuf::from("show s01e02 web.srt")).unwrap();
+        let failed_subtitle_file =
+            SubtitleFile::try_from(path::PathBuf::from("show s01e02 hdtv.srt")).unwrap();
+        let records = [
+            RenameRecord::new(
+                Some(&movie_file),
+                &subtitle_file,
+                Some(path::Path::new("show.s01e02.srt")),
+                RenameStatus::Renamed,
+            ),
+            RenameRecord::new(
+                Some(&movie_file),
+                &failed_subtitle_file,
+                Some(path::Path::new("show.s01e02.srt")),
+                RenameStatus::Failed,
+            ),
+        ];
+
+        let mut rename_log = Vec::new();
+        for record in &records {
+            write_json_line(&mut rename_log, record).unwrap();
+        }
+
+        assert_eq!(
+            String::from_utf8(rename_log).unwrap(),
+            "{\"movie\":\"show.s01e02.mkv\",\"subtitle\":\"show s01e02 web.srt\",\"old_name\":\"show s01e02 web.srt\",\"new_name\":\"show.s01e02.srt\",\"status\":\"renamed\"}\n\
+             {\"movie\":\"show.s01e02.mkv\",\"subtitle\":\"show s01e02 hdtv.srt\",\"old_name\":\"show s01e02 hdtv.srt\",\"new_name\":\"show.s01e02.srt\",\"status\":\"failed\"}\n"
+        );
+    }
+
+    #[test]
+    fn summary_test() {
+        let movie_file = MovieFile::new(path::PathBuf::from("show.s01e02.mkv"), None).unwrap();
+        let subtitle_file =
+            SubtitleFile::try_from(path::PathBuf::from("show s01e02 web.srt")).unwrap();
+        let unmatched_subtitle_file =
+            SubtitleFile::try_from(path::PathBuf::from("show s01e04 web.srt")).unwrap();
+        let unmatched_movie_file =
+            MovieFile::new(path::PathBuf::from("show.s01e03.mkv"), None).unwrap();
+        let records = [
+            RenameRecord::new(
+                Some(&movie_file),
+                &subtitle_file,
+                Some(path::Path::new("show.s01e02.srt")),
+                RenameStatus::Renamed,
+            ),
+            RenameRecord::new(
+                None,
+                &unmatched_subtitle_file,
+                None,
+                RenameStatus::Unmatched,
+            ),
+            RenameRecord::unmatched_movie(&unmatched_movie_file),
+        ];
+
+        let mut summary = Vec::new();
+        write_summary(&mut summary, &RunSummary::new(false, &records)).unwrap();
+
+        assert_eq!(
+            String::from_utf8(summary).unwrap(),
+            "-------------- SUMMARY --------------\n\
+             Renamed          : 1\n\
+             Skipped          : 0\n\
+             Failed           : 0\n\
+             Unmatched subs   : 1\n\
+             Unmatched movies : 1\n"
+        );
+
+        // Dry runs count the subtitle files that would be renamed
+        let dry_run_records = [
+            RenameRecord::new(
+                Some(&movie_file),
+                &subtitle_file,
+                Some(path::Path::new("show.s01e02.srt")),
+                RenameStatus::WouldRename,
+            ),
+            RenameRecord::unmatched_movie(&unmatched_movie_file),
+        ];
+        let dry_run_summary = RunSummary::new(true, &dry_run_records);
+
+        let mut summary = Vec::new();
+        write_summary(&mut summary, &dry_run_summary).unwrap();
+        assert_eq!(
+            String::from_utf8(summary).unwrap(),
+            "-------------- SUMMARY --------------\n\
+             Would rename     : 1\n\
+             Skipped          : 0\n\
+             Failed           : 0\n\
+             Unmatched subs   : 0\n\
+             Unmatched movies : 1\n"
+        );
+        assert_eq!(
+            dry_run_summary.to_json(),
+            r#"{"dry_run":true,"renamed":0,"would_rename":1,"skipped":0,"failed":0,"unmatched_subs":0,"unmatched_movies":1}"#
+        );
+    }
+
+    #[test]
+    fn problems_test() {
+        let movie_file = MovieFile::new(path::PathBuf::from("show.s01e02.mkv"), None).unwrap();
+        let subtitle_file =
+            SubtitleFile::try_from(path::PathBuf::from("show s01e02 web.srt")).unwrap();
+        let failed_subtitle_file =
+            SubtitleFile::try_from(path::PathBuf::from("show s01e05 web.srt")).unwrap();
+        let unmatched_subtitle_file =
+            SubtitleFile::try_from(path::PathBuf::from("show s01e04 web.srt")).unwrap();
+        let unmatched_movie_file =
+            MovieFile::new(path::PathBuf::from("show.s01e03.mkv"), None).unwrap();
+        let records = [
+            RenameRecord::new(
+                Some(&movie_file),
+                &subtitle_file,
+                Some(path::Path::new("show.s01e02.srt")),
+                RenameStatus::Renamed,
+            ),
+            RenameRecord::new(
+                Some(&movie_file),
+                &failed_subtitle_file,
+                Some(path::Path::new("show.s01e05.srt")),
+                RenameStatus::Failed,
+            ),
+            RenameRecord::new(
+                None,
+                &unmatched_subtitle_file,
+                None,
+                RenameStatus::Unmatched,
+            ),
+            RenameRecord::unmatched_movie(&unmatched_movie_file),
+        ];
+
+        let mut problems = Vec::new();
+        write_problems(&mut problems, &records).unwrap();
+
+        assert_eq!(
+            String::from_utf8(problems).unwrap(),
+            "Failed             : show s01e05 web.srt\n\
+             Unmatched subtitle : show s01e04 web.srt\n\
+             Unmatched movie    : show.s01e03.mkv\n"
+        );
+    }
+
+    #[test]
+    fn groups_by_show_test() {
+        let record = |movie: Option<&str>, subtitle, new_name: Option<&str>, status| {
+            let movie_file =
+                movie.map(|movie| MovieFile::new(path::PathBuf::from(movie), None).unwrap());
+            let subtitle_file = SubtitleFile::try_from(path::PathBuf::from(subtitle)).unwrap();
+            RenameRecord::new(
+                movie_file.as_ref(),
+                &subtitle_file,
+                new_name.map(path::Path::new),
+                status,
+            )
+        };
+        let unmatched_movie_file =
+            MovieFile::new(path::PathBuf::from("The.Wire.S01E03.mkv"), None).unwrap();
+        let records = [
+            record(
+                Some("The.Wire.S01E01.mkv"),
+                "the wire s01e01 web.srt",
+                Some("The.Wire.S01E01.srt"),
+                RenameStatus::Renamed,
+            ),
+            record(
+                Some("Breaking.Bad.S01E01.mkv"),
+                "breaking bad s01e01 web.srt",
+                Some("Breaking.Bad.S01E01.srt"),
+                RenameStatus::Renamed,
+            ),
+            record(
+                None,
+                "breaking bad s01e02 web.srt",
+                None,
+                RenameStatus::Unmatched,
+            ),
+            RenameRecord::unmatched_movie(&unmatched_movie_file),
+        ];
+
+        let mut groups = Vec::new();
+        write_groups_by_show(&mut groups, &records).unwrap();
+
+        assert_eq!(
+            String::from_utf8(groups).unwrap(),
+            ":: Breaking Bad (2 files)\n\
+             - renamed : breaking bad s01e01 web.srt -> Breaking.Bad.S01E01.srt\n\
+             - unmatched : breaking bad s01e02 web.srt\n\
+             :: The Wire (2 files)\n\
+             - renamed : the wire s01e01 web.srt -> The.Wire.S01E01.srt\n\
+             - unmatched : The.Wire.S01E03.mkv\n"
+        );
+    }
+}
@@ -0,0 +1,122 @@
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, UTF_8};
+use std::borrow::Cow;
+
+/// Separator between the start and end times of an SRT cue, as in
+/// "00:00:01,000 --> 00:00:02,500"
+const TIMING_SEPARATOR: &str = "-->";
+
+/// How many code units of a content without a byte order mark are checked to guess whether it
+/// is UTF-16
+const UTF16_GUESS_LENGTH: usize = 16;
+
+/// Decodes the bytes of an SRT content, using the encoding of its byte order mark when it has
+/// one. Contents without a byte order mark are read as UTF-16 when every other byte of their
+/// start is zero, as happens with ASCII text, and as UTF-8 otherwise, the bytes of other
+/// encodings like Latin-1 being replaced without affecting the ASCII structure of the content
+pub fn decode(bytes: &[u8]) -> Cow<'_, str> {
+    let encoding = match Encoding::for_bom(bytes) {
+        Some((encoding, bom_length)) => {
+            return encoding.decode_without_bom_handling(&bytes[bom_length..]).0
+        }
+        None => guess_utf16(bytes).unwrap_or(UTF_8),
+    };
+
+    encoding.decode_without_bom_handling(bytes).0
+}
+
+/// Returns the UTF-16 encoding of the bytes when they look like UTF-16 encoded ASCII text, that
+/// is when the high bytes of the first code units are all zero
+fn guess_utf16(bytes: &[u8]) -> Option<&'static Encoding> {
+    let code_units: Vec<&[u8]> = bytes.chunks_exact(2).take(UTF16_GUESS_LENGTH).collect();
+    if code_units.is_empty() {
+        return None;
+    }
+
+    if code_units.iter().all(|unit| unit[0] != 0 && unit[1] == 0) {
+        Some(UTF_16LE)
+    } else if code_units.iter().all(|unit| unit[0] == 0 && unit[1] != 0) {
+        Some(UTF_16BE)
+    } else {
+        None
+    }
+}
+
+/// Returns whether the content looks like SRT, that is its first non-empty lines are a cue
+/// number followed by a timing line like "00:00:01,000 --> 00:00:02,500". Only the start
+/// of the content is needed
+pub fn looks_like_srt(content: &str) -> bool {
+    let mut lines = content
+        .trim_start_matches('\u{feff}')
+        .lines()
+        .map(str::trim)
+        .skip_while(|line| line.is_empty());
+
+    let (Some(cue_number), Some(timing)) = (lines.next(), lines.next()) else {
+        return false;
+    };
+
+    !cue_number.is_empty()
+        && cue_number.chars().all(|x| x.is_ascii_digit())
+        && timing
+            .split_once(TIMING_SEPARATOR)
+            .is_some_and(|(start, end)| is_timestamp(start.trim()) && is_timestamp(end.trim()))
+}
+
+/// Returns whether the string is an SRT timestamp like "00:00:01,000". A dot is accepted in
+/// place of the comma as some subtitle files use it
+fn is_timestamp(string: &str) -> bool {
+    let Some((time, milliseconds)) = string.split_once([',', '.']) else {
+        return false;
+    };
+
+    let time_parts: Vec<&str> = time.split(':').collect();
+    time_parts.len() == 3
+        && time_parts
+            .iter()
+            .chain([&milliseconds])
+            .all(|part| !part.is_empty() && part.chars().all(|x| x.is_ascii_digit()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn looks_like_srt_test() {
+        assert!(looks_like_srt(
+            "\u{feff}1\r\n00:00:01,000 --> 00:00:02,500\r\nHello there\r\n"
+        ));
+        assert!(looks_like_srt("\n\n12\n00:01:01.000 --> 00:01:02.000\n"));
+        assert!(!looks_like_srt(
+            "Some notes about the release\n1080p x264\n"
+        ));
+        assert!(!looks_like_srt("1\n"));
+    }
+
+    #[test]
+    fn decode_test() {
+        let srt = "1\r\n00:00:01,000 --> 00:00:02,500\r\nHello there\r\n";
+
+        let utf8_with_bom = [&[0xEF, 0xBB, 0xBF], srt.as_bytes()].concat();
+        assert_eq!(decode(&utf8_with_bom), srt);
+
+        let utf16le_with_bom: Vec<u8> = [0xFF, 0xFE]
+            .into_iter()
+            .chain(srt.encode_utf16().flat_map(u16::to_le_bytes))
+            .collect();
+        assert_eq!(decode(&utf16le_with_bom), srt);
+
+        let utf16be_with_bom: Vec<u8> = [0xFE, 0xFF]
+            .into_iter()
+            .chain(srt.encode_utf16().flat_map(u16::to_be_bytes))
+            .collect();
+        assert_eq!(decode(&utf16be_with_bom), srt);
+
+        let utf16le: Vec<u8> = srt.encode_utf16().flat_map(u16::to_le_bytes).collect();
+        assert_eq!(decode(&utf16le), srt);
+
+        // The "é" of Latin-1 is not valid UTF-8 but the structure is still read
+        let latin1 = b"1\n00:00:01,000 --> 00:00:02,500\nCaf\xe9\n";
+        assert!(looks_like_srt(&decode(latin1)));
+    }
+}
@@ -0,0 +1,236 @@
+use crate::report::RunStats;
+use crate::ScannedFile;
+use colored::*;
+use std::collections::HashMap;
+use sub_auto_rename::{MatchKey, MovieFile, RenameOptions, RenameOutcome, SubtitleFile};
+
+/// Renames the subtitle files as soon as the movie files they match are found, only keeping the
+/// files that have not been paired yet in memory. The files are paired the way they are when
+/// all of them are collected first, only looking among the pending files having the same key
+pub struct StreamingRenamer<'a> {
+    rename_options: &'a RenameOptions,
+    print_renames: bool,
+    pending_movie_files: HashMap<MatchKey, Vec<MovieFile>>,
+    pending_subtitle_files: HashMap<MatchKey, Vec<SubtitleFile>>,
+    pending_files: usize,
+    peak_pending_files: usize,
+    stats: RunStats,
+}
+
+impl<'a> StreamingRenamer<'a> {
+    /// Constructs a StreamingRenamer renaming with the given options, optionally printing
+    /// every rename
+    pub fn new(rename_options: &'a RenameOptions, print_renames: bool) -> Self {
+        Self {
+            rename_options,
+            print_renames,
+            pending_movie_files: HashMap::new(),
+            pending_subtitle_files: HashMap::new(),
+            pending_files: 0,
+            peak_pending_files: 0,
+            stats: RunStats::default(),
+        }
+    }
+
+    /// Pairs the scanned file with the first pending file it matches, renaming the subtitle
+    /// file right away, or keeps it pending until such file is found
+    pub fn add(&mut self, scanned_file: ScannedFile) {
+        let rename_options = self.rename_options;
+
+        match scanned_file {
+            ScannedFile::Movie(movie_file) => {
+                let Some(match_key) = movie_file.match_key(rename_options) else {
+                    self.stats.unmatched_movies += 1;
+                    return;
+                };
+
+                let subtitle_file = take_pending(
+                    &mut self.pending_subtitle_files,
+                    match_key,
+                    |subtitle_file| {
+                        subtitle_file
+                            .planned_path(&movie_file, rename_options)
+                            .is_some()
+                    },
+                );
+                match subtitle_file {
+                    Some(subtitle_file) => {
+                        self.pending_files -= 1;
+                        self.rename(&movie_file, &subtitle_file);
+                    }
+                    None => {
+                        self.pending_movie_files
+                            .entry(match_key)
+                            .or_default()
+                            .push(movie_file);
+                        self.pending_files += 1;
+                    }
+                }
+            }
+            ScannedFile::Subtitle(subtitle_file) => {
+                let Some(match_key) = subtitle_file.match_key(rename_options) else {
+                    self.stats.unmatched_subs += 1;
+                    return;
+                };
+
+                let movie_file =
+                    take_pending(&mut self.pending_movie_files, match_key, |movie_file| {
+                        subtitle_file
+                            .planned_path(movie_file, rename_options)
+                            .is_some()
+                    });
+                match movie_file {
+                    Some(movie_file) => {
+                        self.pending_files -= 1;
+                        self.rename(&movie_file, &subtitle_file);
+                    }
+                    None => {
+                        self.pending_subtitle_files
+                            .entry(match_key)
+                            .or_default()
+                            .push(subtitle_file);
+                        self.pending_files += 1;
+                    }
+                }
+            }
+        }
+
+        self.peak_pending_files = self.peak_pending_files.max(self.pending_files);
+    }
+
+    /// Returns the statistics of the run, the files still pending being unmatched
+    pub fn finish(mut self) -> RunStats {
+        self.stats.unmatched_movies += self
+            .pending_movie_files
+            .values()
+            .map(Vec::len)
+            .sum::<usize>();
+        self.stats.unmatched_subs += self
+            .pending_subtitle_files
+            .values()
+            .map(Vec::len)
+            .sum::<usize>();
+        self.stats
+    }
+
+    /// Renames the subtitle file using the movie file
+    fn rename(&mut self, movie_file: &MovieFile, subtitle_file: &SubtitleFile) {
+        match subtitle_file.rename_using_movie_file_with_options(movie_file, self.rename_options) {
+            Ok(RenameOutcome::Moved(_)) => {
+                if self.print_renames {
+                    println!("{} Renamed subtitle file '{}'", "->".green(), subtitle_file);
+                }
+                self.stats.renamed += 1;
+            }
+            Ok(RenameOutcome::AlreadyCorrect) => {
+                log::warn!("Skipping already renamed file: '{}'", subtitle_file);
+                self.stats.skipped += 1;
+            }
+            Err(err) => {
+                log::error!("{}", err);
+                log::warn!("Skipping errored file: '{}'", subtitle_file);
+                self.stats.errors += 1;
+            }
+        }
+    }
+}
+
+/// Takes the first of the pending files with the given key satisfying the predicate out of the
+/// pending files
+fn take_pending<T>(
+    pending_files: &mut HashMap<MatchKey, Vec<T>>,
+    match_key: MatchKey,
+    predicate: impl Fn(&T) -> bool,
+) -> Option<T> {
+    let files = pending_files.get_mut(&match_key)?;
+    let index = files.iter().position(predicate)?;
+    let file = files.remove(index);
+    if files.is_empty() {
+        pending_files.remove(&match_key);
+    }
+
+    Some(file)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StreamingRenamer;
+    use crate::test_directory::test_directory;
+    use crate::{scan_files, ScanOptions};
+    use std::fs;
+    use sub_auto_rename::RenameOptions;
+
+    #[test]
+    fn streaming_rename_large_directory_test() {
+        let directory = test_directory("streaming");
+        for episode in 1..=1000 {
+            fs::write(directory.join(format!("Show.S01E{:04}.mkv", episode)), "").unwrap();
+            fs::write(
+                directory.join(format!("Show.S01E{:04}.WEB.srt", episode)),
+                "",
+            )
+            .unwrap();
+        }
+
+        let rename_options = RenameOptions::default();
+        let mut streaming_renamer = StreamingRenamer::new(&rename_options, false);
+        let scan_options = ScanOptions {
+            depth: 1,
+            ..Default::default()
+        };
+        scan_files(&directory, &scan_options, |scanned_file| {
+            streaming_renamer.add(scanned_file)
+        })
+        .unwrap();
+
+        // The files are scanned by name, each subtitle file comes right before its movie file
+        // here, so a single file is pending at a time however many files there are
+        assert_eq!(streaming_renamer.peak_pending_files, 1);
+        let stats = streaming_renamer.finish();
+        assert_eq!(stats.renamed, 1000);
+        assert_eq!(stats.unmatched_movies + stats.unmatched_subs, 0);
+        assert!(directory.join("Show.S01E1000.srt").exists());
+    }
+
+    #[test]
+    fn streaming_rename_options_test() {
+        let directory = test_directory("streaming-options");
+        for file_name in [
+            // Two movie files sharing a signature, both pending at once
+            "Show.S01E04.720p.mkv",
+            "Show.S01E04.mkv",
+            "show s01e03 a.srt",
+            "show s01e03 b.srt",
+            // A subtitle file with the right signature but another title
+            "Show.S01E06.mkv",
+            "Other.S01E05.srt",
+            "show s01e05 web.srt",
+        ] {
+            fs::write(directory.join(file_name), "").unwrap();
+        }
+
+        // The episodes of the subtitle files are shifted by the offset
+        let rename_options = RenameOptions {
+            episode_offset: 1,
+            ..Default::default()
+        };
+        let mut streaming_renamer = StreamingRenamer::new(&rename_options, false);
+        let scan_options = ScanOptions {
+            depth: 1,
+            ..Default::default()
+        };
+        scan_files(&directory, &scan_options, |scanned_file| {
+            streaming_renamer.add(scanned_file)
+        })
+        .unwrap();
+
+        let stats = streaming_renamer.finish();
+        assert_eq!(stats.renamed, 3);
+        assert_eq!(stats.unmatched_movies, 0);
+        assert_eq!(stats.unmatched_subs, 1);
+        assert!(directory.join("Show.S01E04.720p.srt").exists());
+        assert!(directory.join("Show.S01E04.srt").exists());
+        assert!(directory.join("Show.S01E06.srt").exists());
+        assert!(directory.join("Other.S01E05.srt").exists());
+    }
+}
@@ -0,0 +1,256 @@
+use crate::name_signature::{get_signature, get_signature_end, get_title_and_year};
+use std::ffi::OsStr;
+
+/// Characters separating the words of a file name
+const WORD_SEPARATORS: &[char] = &[' ', '.', '-', '_'];
+
+/// Modifiers written between the stem and the extension of subtitle files, like the "forced"
+/// of "name.forced.srt"
+const SUBTITLE_MODIFIERS: &[&str] = &["forced", "sdh", "hi", "cc"];
+
+/// ISO 639-1 and common ISO 639-2 language codes, written between the stem and the extension
+/// of subtitle files like the "en" of "name.en.srt"
+const LANGUAGE_CODES: &[&str] = &[
+    "aa", "ab", "ae", "af", "ak", "am", "an", "ar", "as", "av", "ay", "az", "ba", "be", "bg", "bh",
+    "bi", "bm", "bn", "bo", "br", "bs", "ca", "ce", "ch", "co", "cr", "cs", "cu", "cv", "cy", "da",
+    "de", "dv", "dz", "ee", "el", "en", "eo", "es", "et", "eu", "fa", "ff", "fi", "fj", "fo", "fr",
+    "fy", "ga", "gd", "gl", "gn", "gu", "gv", "ha", "he", "hi", "ho", "hr", "ht", "hu", "hy", "hz",
+    "ia", "id", "ie", "ig", "ii", "ik", "io", "is", "it", "iu", "ja", "jv", "ka", "kg", "ki", "kj",
+    "kk", "kl", "km", "kn", "ko", "kr", "ks", "ku", "kv", "kw", "ky", "la", "lb", "lg", "li", "ln",
+    "lo", "lt", "lu", "lv", "mg", "mh", "mi", "mk", "ml", "mn", "mr", "ms", "mt", "my", "na", "nb",
+    "nd", "ne", "ng", "nl", "nn", "no", "nr", "nv", "ny", "oc", "oj", "om", "or", "os", "pa", "pi",
+    "pl", "ps", "pt", "qu", "rm", "rn", "ro", "ru", "rw", "sa", "sc", "sd", "se", "sg", "si", "sk",
+    "sl", "sm", "sn", "so", "sq", "sr", "ss", "st", "su", "sv", "sw", "ta", "te", "tg", "th", "ti",
+    "tk", "tl", "tn", "to", "tr", "ts", "tt", "tw", "ty", "ug", "uk", "ur", "uz", "ve", "vi", "vo",
+    "wa", "wo", "xh", "yi", "yo", "za", "zh", "zu", "ara", "ces", "chi", "cze", "dan", "deu",
+    "dut", "ell", "eng", "fin", "fra", "fre", "ger", "gre", "heb", "hin", "hun", "ind", "ita",
+    "jpn", "kor", "nld", "nor", "pol", "por", "ron", "rum", "rus", "spa", "swe", "tha", "tur",
+    "ukr", "vie", "zho",
+];
+
+/// Returns the trailing modifiers of the subtitle file stem with their leading dots, like
+/// ".forced" for "Show S01E02.forced", or an empty string when there are none
+pub fn subtitle_modifiers(file_stem: &str) -> &str {
+    let mut start = file_stem.len();
+    while let Some(dot_index) = file_stem[..start].rfind('.') {
+        let segment = &file_stem[dot_index + 1..start];
+        if !SUBTITLE_MODIFIERS
+            .iter()
+            .any(|modifier| modifier.eq_ignore_ascii_case(segment))
+        {
+            break;
+        }
+        start = dot_index;
+    }
+
+    &file_stem[start..]
+}
+
+/// Strips the trailing " (N)" marker browsers add to the names of files downloaded more than
+/// once, like the " (1)" of "Show.S01E02 (1)"
+pub fn strip_duplicate_marker(file_stem: &str) -> &str {
+    let marker_number = file_stem
+        .strip_suffix(')')
+        .and_then(|rest| rest.rsplit_once(" ("))
+        .filter(|(_, number)| !number.is_empty() && number.chars().all(|x| x.is_ascii_digit()));
+
+    match marker_number {
+        Some((stripped_file_stem, _)) if !stripped_file_stem.is_empty() => stripped_file_stem,
+        _ => file_stem,
+    }
+}
+
+/// Returns the language code written right before the modifiers of the subtitle file stem
+/// with its leading dot, like ".en" for "Show S01E02.en.forced", or an empty string when there
+/// is none. A region may follow the code, as in ".pt-BR"
+pub fn language_tag(file_stem: &str) -> &str {
+    let file_stem = &file_stem[..file_stem.len() - subtitle_modifiers(file_stem).len()];
+    let Some(dot_index) = file_stem.rfind('.') else {
+        return "";
+    };
+
+    let segment = &file_stem[dot_index + 1..];
+    let (language, region) = match segment.split_once(['-', '_']) {
+        Some((language, region)) => (language, Some(region)),
+        None => (segment, None),
+    };
+    let is_language = LANGUAGE_CODES
+        .iter()
+        .any(|code| code.eq_ignore_ascii_case(language));
+    let is_region = region
+        .is_none_or(|region| region.len() == 2 && region.chars().all(|x| x.is_ascii_alphabetic()));
+
+    if is_language && is_region {
+        &file_stem[dot_index..]
+    } else {
+        ""
+    }
+}
+
+/// Returns the single suffix mpv reads between the stem of the movie file and the extension for
+/// the subtitle file stem, that is its language tag when it has one, like ".en" for
+/// "Show S01E02.en.forced", and otherwise the last of its modifiers lowercased, like ".sdh" for
+/// "Show S01E02.forced.SDH". Returns an empty string when the stem has neither
+pub fn mpv_suffix(file_stem: &str) -> String {
+    let language = language_tag(file_stem);
+    if !language.is_empty() {
+        return language.to_string();
+    }
+
+    let modifiers = subtitle_modifiers(file_stem);
+    match modifiers.rfind('.') {
+        Some(dot_index) => modifiers[dot_index..].to_lowercase(),
+        None => String::new(),
+    }
+}
+
+/// Strips a trailing release group tag like "-RARBG" or "[EtHD]" from the file stem
+pub fn strip_release_group(file_stem: &str) -> &str {
+    let trimmed_file_stem = file_stem.trim_end();
+
+    let group_start = if trimmed_file_stem.ends_with(']') {
+        trimmed_file_stem.rfind('[')
+    } else {
+        // The group has to be a single word, that way hyphenated titles like "Spider-Man"
+        // or separators like "Show - S01E02" are not mistaken for groups
+        trimmed_file_stem.rfind('-').filter(|index| {
+            let group = &trimmed_file_stem[index + 1..];
+            !group.is_empty() && group.chars().all(char::is_alphanumeric)
+        })
+    };
+
+    match group_start {
+        Some(group_start) => {
+            let stripped_file_stem =
+                trimmed_file_stem[..group_start].trim_end_matches(WORD_SEPARATORS);
+            if stripped_file_stem.is_empty() {
+                file_stem
+            } else {
+                stripped_file_stem
+            }
+        }
+        None => file_stem,
+    }
+}
+
+/// Returns the stem Plex recommends for the episode named by the file stem, that is
+/// "Show Name (2019) - s01e02 - Episode Title", the year and the episode title only being
+/// there when the file stem has them. The episode title is only known when the file stem
+/// already follows that form. Returns None when the file stem has no title or signature
+pub fn plex_stem(file_stem: &str) -> Option<String> {
+    let (title, year) = get_title_and_year(file_stem)?;
+    let signature = get_signature(OsStr::new(file_stem))?;
+    let signature_end = get_signature_end(file_stem)?;
+
+    let mut plex_stem = title.replace(['.', '_'], " ");
+    if let Some(year) = year {
+        plex_stem.push_str(&format!(" ({})", year));
+    }
+    plex_stem.push_str(&format!(
+        " - s{:02}e{:02}",
+        signature.season, signature.episode
+    ));
+    if let Some(episode_suffix) = signature.episode_suffix {
+        plex_stem.push(episode_suffix.to_ascii_lowercase());
+    }
+
+    let episode_title = file_stem[signature_end..]
+        .strip_prefix(" - ")
+        .map(str::trim)
+        .filter(|episode_title| !episode_title.is_empty());
+    if let Some(episode_title) = episode_title {
+        plex_stem.push_str(" - ");
+        plex_stem.push_str(episode_title);
+    }
+
+    Some(plex_stem)
+}
+
+/// Replaces the dots separating the words of the file stem with spaces
+pub fn dots_to_spaces(file_stem: &str) -> String {
+    file_stem.replace('.', " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strip_release_group_test() {
+        assert_eq!(
+            strip_release_group("Show.S01E02.720p.WEB-RARBG"),
+            "Show.S01E02.720p.WEB"
+        );
+        assert_eq!(strip_release_group("Show.S01E02 [EtHD]"), "Show.S01E02");
+        assert_eq!(strip_release_group("Show - S01E02"), "Show - S01E02");
+        assert_eq!(
+            strip_release_group("Spider-Man.S01E02"),
+            "Spider-Man.S01E02"
+        );
+        assert_eq!(strip_release_group("[EtHD]"), "[EtHD]");
+    }
+
+    #[test]
+    fn subtitle_modifiers_test() {
+        assert_eq!(subtitle_modifiers("show s01e02.forced"), ".forced");
+        assert_eq!(subtitle_modifiers("show s01e02.SDH"), ".SDH");
+        assert_eq!(subtitle_modifiers("show s01e02.forced.sdh"), ".forced.sdh");
+        assert_eq!(subtitle_modifiers("show.s01e02.web"), "");
+        assert_eq!(subtitle_modifiers("forced"), "");
+    }
+
+    #[test]
+    fn strip_duplicate_marker_test() {
+        assert_eq!(strip_duplicate_marker("Show.s01e02 (1)"), "Show.s01e02");
+        assert_eq!(
+            strip_duplicate_marker("Show.s01e02.forced (2)"),
+            "Show.s01e02.forced"
+        );
+        assert_eq!(
+            strip_duplicate_marker("Show (2019) S01E02"),
+            "Show (2019) S01E02"
+        );
+        assert_eq!(
+            strip_duplicate_marker("Show.s01e02 (web)"),
+            "Show.s01e02 (web)"
+        );
+        assert_eq!(strip_duplicate_marker(" (1)"), " (1)");
+    }
+
+    #[test]
+    fn plex_stem_test() {
+        assert_eq!(
+            plex_stem("Breaking.Bad.S01E02.720p.WEB-GRP").as_deref(),
+            Some("Breaking Bad - s01e02")
+        );
+        assert_eq!(
+            plex_stem("Show Name (2019) - s01e02 - Episode Title").as_deref(),
+            Some("Show Name (2019) - s01e02 - Episode Title")
+        );
+        assert_eq!(
+            plex_stem("Grey's.Anatomy.2005.S01E02a").as_deref(),
+            Some("Grey's Anatomy (2005) - s01e02a")
+        );
+        assert_eq!(plex_stem("S01E02"), None);
+        assert_eq!(plex_stem("Show"), None);
+    }
+
+    #[test]
+    fn language_tag_test() {
+        assert_eq!(language_tag("show s01e02.en"), ".en");
+        assert_eq!(language_tag("show s01e02.en.forced"), ".en");
+        assert_eq!(language_tag("show s01e02.pt-BR.SDH"), ".pt-BR");
+        assert_eq!(language_tag("show s01e02.eng"), ".eng");
+        assert_eq!(language_tag("show.s01e02.web"), "");
+        assert_eq!(language_tag("show.s01e02.forced"), "");
+        assert_eq!(language_tag("en"), "");
+    }
+
+    #[test]
+    fn mpv_suffix_test() {
+        assert_eq!(mpv_suffix("show s01e02.en.forced"), ".en");
+        assert_eq!(mpv_suffix("show s01e02.forced.SDH"), ".sdh");
+        assert_eq!(mpv_suffix("show s01e02.Forced"), ".forced");
+        assert_eq!(mpv_suffix("show s01e02"), "");
+    }
+}
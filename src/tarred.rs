@@ -0,0 +1,198 @@
+use crate::{MovieFile, RenameOptions, SubtitleFile, SubtitleFileError};
+use std::collections::HashMap;
+use std::fs;
+use std::path;
+
+/// Writes a copy of the tar archive, like a season kept as a single "season.tar", to the output
+/// path with its subtitle file entries renamed after the movie file entries they match,
+/// returning the old and new paths of the renamed entries. Entries are matched the way files
+/// of a directory are, the first movie file entry matching a subtitle file entry being taken.
+/// The archive itself is left alone
+///
+/// # Errors
+/// This function returns [`SubtitleFileError::TargetExists`] when the output path already
+/// exists, and an error when reading the archive or writing the copy fails
+pub fn rename_tar_entries(
+    archive_path: &path::Path,
+    output_path: &path::Path,
+    options: &RenameOptions,
+) -> Result<Vec<(path::PathBuf, path::PathBuf)>, SubtitleFileError> {
+    if output_path.exists() {
+        return Err(SubtitleFileError::TargetExists(output_path.to_path_buf()));
+    }
+
+    let renamed_entries = plan_entry_renames(archive_path, options)
+        .map_err(|err| SubtitleFileError::FileSystem(err.to_string()))?;
+
+    if let Err(err) = write_renamed_archive(archive_path, output_path, &renamed_entries) {
+        // Not leaving a partially written archive behind
+        let _ = fs::remove_file(output_path);
+        return Err(SubtitleFileError::FileSystem(err.to_string()));
+    }
+
+    let mut renamed_entries: Vec<_> = renamed_entries.into_iter().collect();
+    renamed_entries.sort_unstable();
+    Ok(renamed_entries)
+}
+
+/// Returns the new paths of the subtitle file entries of the archive matching a movie file
+/// entry, keyed by their current paths. Entries are never renamed to the path of another one
+fn plan_entry_renames(
+    archive_path: &path::Path,
+    options: &RenameOptions,
+) -> std::io::Result<HashMap<path::PathBuf, path::PathBuf>> {
+    let mut movie_files = Vec::new();
+    let mut subtitle_files = Vec::new();
+    let mut entry_paths = std::collections::HashSet::new();
+
+    let mut archive = tar::Archive::new(fs::File::open(archive_path)?);
+    for entry in archive.entries()? {
+        let entry = entry?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+
+        let entry_path = entry.path()?.into_owned();
+        entry_paths.insert(entry_path.clone());
+        if let Some(movie_file) = MovieFile::new(entry_path.clone(), None) {
+            movie_files.push(movie_file);
+        } else if let Ok(subtitle_file) = SubtitleFile::try_from(entry_path) {
+            subtitle_files.push(subtitle_file);
+        }
+    }
+
+    let mut movie_file_paired = vec![false; movie_files.len()];
+    let mut renamed_entries = HashMap::new();
+    for subtitle_file in &subtitle_files {
+        let planned_rename = movie_files
+            .iter()
+            .enumerate()
+            .filter(|(movie_index, _)| !movie_file_paired[*movie_index])
+            .find_map(|(movie_index, movie_file)| {
+                subtitle_file
+                    .planned_path(movie_file, options)
+                    .map(|new_path| (movie_index, new_path))
+            });
+
+        if let Some((movie_index, new_path)) = planned_rename {
+            movie_file_paired[movie_index] = true;
+            if entry_paths.contains(&new_path) {
+                if new_path != subtitle_file.path() {
+                    log::warn!(
+                        "Not renaming '{}' as '{}' is already in the archive",
+                        subtitle_file,
+                        new_path.display()
+                    );
+                }
+            } else {
+                renamed_entries.insert(subtitle_file.path().to_path_buf(), new_path);
+            }
+        }
+    }
+
+    Ok(renamed_entries)
+}
+
+/// Copies every entry of the archive to the output path, giving the renamed entries their
+/// new paths. The entries are written in the order of the archive
+fn write_renamed_archive(
+    archive_path: &path::Path,
+    output_path: &path::Path,
+    renamed_entries: &HashMap<path::PathBuf, path::PathBuf>,
+) -> std::io::Result<()> {
+    let mut archive = tar::Archive::new(fs::File::open(archive_path)?);
+    let mut builder = tar::Builder::new(fs::File::create(output_path)?);
+
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let entry_path = entry.path()?.into_owned();
+        let new_entry_path = renamed_entries.get(&entry_path).unwrap_or(&entry_path);
+
+        let mut header = entry.header().clone();
+        builder.append_data(&mut header, new_entry_path, &mut entry)?;
+    }
+
+    builder.into_inner()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::rename_tar_entries;
+    use crate::test_directory::test_directory;
+    use crate::{RenameOptions, SubtitleFileError};
+    use std::fs;
+    use std::io::Read;
+    use std::path;
+
+    #[test]
+    fn rename_tar_entries_round_trip_test() {
+        let directory = test_directory("tarred");
+        let archive_path = directory.join("season.tar");
+        let output_path = directory.join("season.renamed.tar");
+
+        let mut builder = tar::Builder::new(fs::File::create(&archive_path).unwrap());
+        for (entry_path, content) in [
+            ("season/Show.S01E01.mkv", ""),
+            ("season/Show.S01E02.mkv", ""),
+            ("season/show s01e02 web.srt", "subtitle"),
+            ("season/Show.S01E01.srt", "already renamed"),
+            ("season/notes.txt", "release notes"),
+        ] {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            builder
+                .append_data(&mut header, entry_path, content.as_bytes())
+                .unwrap();
+        }
+        builder.into_inner().unwrap();
+
+        let renamed_entries =
+            rename_tar_entries(&archive_path, &output_path, &RenameOptions::default()).unwrap();
+        assert_eq!(
+            renamed_entries,
+            vec![(
+                path::PathBuf::from("season/show s01e02 web.srt"),
+                path::PathBuf::from("season/Show.S01E02.srt")
+            )]
+        );
+
+        let mut entries = Vec::new();
+        let mut archive = tar::Archive::new(fs::File::open(&output_path).unwrap());
+        for entry in archive.entries().unwrap() {
+            let mut entry = entry.unwrap();
+            let mut content = String::new();
+            entry.read_to_string(&mut content).unwrap();
+            entries.push((
+                entry.path().unwrap().to_string_lossy().into_owned(),
+                content,
+            ));
+        }
+        assert_eq!(entries.len(), 5);
+        assert!(entries.contains(&("season/Show.S01E02.srt".to_string(), "subtitle".to_string())));
+        assert!(entries.contains(&("season/notes.txt".to_string(), "release notes".to_string())));
+
+        // The output is never overwritten
+        assert!(matches!(
+            rename_tar_entries(&archive_path, &output_path, &RenameOptions::default()),
+            Err(SubtitleFileError::TargetExists(_))
+        ));
+
+        // The cause of a failure is part of the error message
+        let missing_archive_path = directory.join("missing.tar");
+        let err = rename_tar_entries(
+            &missing_archive_path,
+            &directory.join("missing.renamed.tar"),
+            &RenameOptions::default(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "There is an error related to the filesystem: {}",
+                fs::File::open(&missing_archive_path).unwrap_err()
+            )
+        );
+    }
+}
@@ -0,0 +1,37 @@
+use std::fs;
+use std::ops::Deref;
+use std::path;
+
+/// An empty directory in the system's temporary directory for tests that need to touch the
+/// filesystem. It is removed once dropped, even when an assertion of the test fails
+pub struct TestDirectory(path::PathBuf);
+
+impl Deref for TestDirectory {
+    type Target = path::Path;
+
+    fn deref(&self) -> &path::Path {
+        &self.0
+    }
+}
+
+impl AsRef<path::Path> for TestDirectory {
+    fn as_ref(&self) -> &path::Path {
+        &self.0
+    }
+}
+
+impl Drop for TestDirectory {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+/// Creates the directory of the test, named after the test and the process. A directory left
+/// behind by an aborted run is emptied first
+pub fn test_directory(name: &str) -> TestDirectory {
+    let directory =
+        std::env::temp_dir().join(format!("sub-auto-rename-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&directory);
+    fs::create_dir_all(&directory).unwrap();
+    TestDirectory(directory)
+}
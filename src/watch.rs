@@ -0,0 +1,117 @@
+use anyhow::Result;
+use notify::event::{AccessKind, AccessMode};
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+use std::path;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::time::Duration;
+
+/// Watches the directory, calling `on_settled` each time files have been added or changed and
+/// then left alone for the settle duration, so that files still being downloaded are not picked
+/// up half written. Errors returned by `on_settled` are logged and the watch goes on
+///
+/// # Errors
+/// This function returns an error when the directory can't be watched
+pub fn watch_directory(
+    directory: &path::Path,
+    recursive: bool,
+    settle: Duration,
+    on_settled: impl FnMut() -> Result<()>,
+) -> Result<()> {
+    let (sender, receiver) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(sender)?;
+    watcher.watch(
+        directory,
+        if recursive {
+            RecursiveMode::Recursive
+        } else {
+            RecursiveMode::NonRecursive
+        },
+    )?;
+
+    handle_changes(&receiver, settle, on_settled);
+    Ok(())
+}
+
+/// Calls `on_settled` each time the changes received have settled, until the watcher sending
+/// them stops
+pub fn handle_changes(
+    receiver: &Receiver<notify::Result<Event>>,
+    settle: Duration,
+    mut on_settled: impl FnMut() -> Result<()>,
+) {
+    while wait_for_settled_changes(receiver, settle) {
+        if let Err(err) = on_settled() {
+            log::error!("{:#}", err);
+        }
+    }
+}
+
+/// Waits for files to be added or changed, then for the settle duration to go by without any
+/// other change. Returns false when the watcher stopped without any change left to handle
+fn wait_for_settled_changes(receiver: &Receiver<notify::Result<Event>>, settle: Duration) -> bool {
+    loop {
+        match receiver.recv() {
+            Ok(event) if is_file_change(&event) => break,
+            Ok(_) => {}
+            Err(_) => return false,
+        }
+    }
+
+    loop {
+        match receiver.recv_timeout(settle) {
+            Ok(event) if is_file_change(&event) => log::debug!("Files are still changing"),
+            Ok(_) => {}
+            // The changes seen so far are still handled when the watcher stops
+            Err(RecvTimeoutError::Timeout | RecvTimeoutError::Disconnected) => return true,
+        }
+    }
+}
+
+/// Returns whether the event is a file being added, written to or renamed, logging the errors
+/// of the watcher
+fn is_file_change(event: &notify::Result<Event>) -> bool {
+    match event {
+        Ok(event) => matches!(
+            event.kind,
+            EventKind::Create(_)
+                | EventKind::Modify(_)
+                | EventKind::Access(AccessKind::Close(AccessMode::Write))
+        ),
+        Err(err) => {
+            log::warn!("Watching the directory failed: {}", err);
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::wait_for_settled_changes;
+    use notify::event::{AccessKind, AccessMode, CreateKind};
+    use notify::{Event, EventKind};
+    use std::path;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    #[test]
+    fn wait_for_settled_changes_test() {
+        let settle = Duration::from_millis(10);
+        let (sender, receiver) = mpsc::channel();
+
+        // A subtitle file appearing in the directory
+        sender
+            .send(Ok(Event::new(EventKind::Create(CreateKind::File))
+                .add_path(path::PathBuf::from("videos/show s01e02.srt"))))
+            .unwrap();
+        assert!(wait_for_settled_changes(&receiver, settle));
+
+        // Files only being read are not changes
+        sender
+            .send(Ok(Event::new(EventKind::Access(AccessKind::Open(
+                AccessMode::Read,
+            )))))
+            .unwrap();
+        drop(sender);
+        assert!(!wait_for_settled_changes(&receiver, settle));
+    }
+}
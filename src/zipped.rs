@@ -0,0 +1,234 @@
+use crate::{MovieFile, RenameOptions, RenameOutcome, SubtitleFile, SubtitleFileError};
+use std::fs;
+use std::path;
+
+const ZIP_FILE_EXTENSION: &str = "zip";
+/// The extension of the archive being written, which replaces the target once complete
+const PARTIAL_ZIP_FILE_EXTENSION: &str = "zip.part";
+
+/// A zip archive holding a single subtitle file, like "show s01e02.zip" holding
+/// "show s01e02 web.srt", which gets renamed after a movie file without being extracted.
+/// Other entries of the archive, like release notes, are kept as they are
+#[derive(Debug)]
+pub struct ZippedSubtitleFile {
+    archive_path: path::PathBuf,
+    entry_name: String,
+}
+
+impl ZippedSubtitleFile {
+    /// Opens the zip archive holding a single subtitle file
+    ///
+    /// # Errors
+    /// This function returns [`SubtitleFileError::InvalidSubtileFileName`] when the file name
+    /// doesn't end with ".zip" or when the archive doesn't hold exactly one subtitle file, and
+    /// an error when the archive can't be read
+    pub fn open(archive_path: path::PathBuf) -> Result<Self, SubtitleFileError> {
+        let is_zip_file = archive_path
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case(ZIP_FILE_EXTENSION));
+        if !is_zip_file {
+            return Err(SubtitleFileError::InvalidSubtileFileName);
+        }
+
+        let archive = open_archive(&archive_path)?;
+        let mut subtitle_entry_names = archive
+            .file_names()
+            .filter(|entry_name| is_subtitle_entry(entry_name));
+
+        match (subtitle_entry_names.next(), subtitle_entry_names.next()) {
+            (Some(entry_name), None) => Ok(Self {
+                entry_name: entry_name.to_string(),
+                archive_path,
+            }),
+            _ => Err(SubtitleFileError::InvalidSubtileFileName),
+        }
+    }
+
+    /// Returns the path of the zip archive
+    pub fn path(&self) -> &path::Path {
+        &self.archive_path
+    }
+
+    /// Returns the name of the subtitle file entry of the archive
+    pub fn entry_name(&self) -> &str {
+        &self.entry_name
+    }
+
+    /// Returns the path of the zip archive and the name of the subtitle file entry once
+    /// renamed using the name of a movie file, without touching the filesystem. The archive
+    /// and the entry take the stem of the movie file, as an extracted subtitle file would.
+    /// Returns None when the entry name and the movie file name don't match, see
+    /// [`SubtitleFile::planned_path`]
+    pub fn planned_path(
+        &self,
+        movie_file: &MovieFile,
+        options: &RenameOptions,
+    ) -> Option<(path::PathBuf, String)> {
+        let entry_path = path::Path::new(&self.entry_name);
+        // Matching the entry as if it was extracted next to the archive
+        let subtitle_file = SubtitleFile::try_from_with_extension_case(
+            self.archive_path.with_file_name(entry_path.file_name()?),
+            true,
+        )
+        .ok()?;
+        let new_subtitle_file_path = subtitle_file.planned_path(movie_file, options)?;
+
+        let new_entry_name = entry_path
+            .with_file_name(new_subtitle_file_path.file_name()?)
+            .to_str()?
+            .to_string();
+        Some((
+            new_subtitle_file_path.with_extension(ZIP_FILE_EXTENSION),
+            new_entry_name,
+        ))
+    }
+
+    /// Renames the zip archive and its subtitle file entry using the name of a movie file,
+    /// returning whether the archive has been moved or was already named after the movie file.
+    /// The renamed archive is written in full before replacing the original one
+    ///
+    /// # Errors
+    /// This function returns errors when the entry name and the movie file name have no
+    /// matching season and episode signatures, when the renamed archive would replace another
+    /// file, or when writing the renamed archive fails
+    pub fn rename_using_movie_file_with_options(
+        &self,
+        movie_file: &MovieFile,
+        options: &RenameOptions,
+    ) -> Result<RenameOutcome, SubtitleFileError> {
+        let (new_archive_path, new_entry_name) = self
+            .planned_path(movie_file, options)
+            .ok_or(SubtitleFileError::MovieSubFileNamesMismatch)?;
+
+        if new_archive_path == self.archive_path && new_entry_name == self.entry_name {
+            return Ok(RenameOutcome::AlreadyCorrect);
+        }
+        if new_archive_path != self.archive_path && new_archive_path.exists() {
+            return Err(SubtitleFileError::TargetExists(new_archive_path));
+        }
+
+        let partial_archive_path = new_archive_path.with_extension(PARTIAL_ZIP_FILE_EXTENSION);
+        if let Err(err) = self.write_renamed_archive(&partial_archive_path, &new_entry_name) {
+            // Not leaving a partially written archive behind
+            let _ = fs::remove_file(&partial_archive_path);
+            return Err(SubtitleFileError::FileSystem(err.to_string()));
+        }
+        if let Err(err) = fs::rename(&partial_archive_path, &new_archive_path) {
+            return Err(SubtitleFileError::FileSystem(err.to_string()));
+        }
+        if new_archive_path != self.archive_path {
+            if let Err(err) = fs::remove_file(&self.archive_path) {
+                return Err(SubtitleFileError::FileSystem(err.to_string()));
+            }
+        }
+
+        Ok(RenameOutcome::Moved(new_archive_path))
+    }
+
+    /// Writes a copy of the archive to the path, with the subtitle file entry renamed. The
+    /// entries are copied without being decompressed
+    fn write_renamed_archive(
+        &self,
+        path: &path::Path,
+        new_entry_name: &str,
+    ) -> zip::result::ZipResult<()> {
+        let mut archive = zip::ZipArchive::new(fs::File::open(&self.archive_path)?)?;
+        let mut writer = zip::ZipWriter::new(fs::File::create(path)?);
+
+        for index in 0..archive.len() {
+            let entry = archive.by_index_raw(index)?;
+            if entry.name() == self.entry_name {
+                writer.raw_copy_file_rename(entry, new_entry_name)?;
+            } else {
+                writer.raw_copy_file(entry)?;
+            }
+        }
+
+        writer.finish()?;
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for ZippedSubtitleFile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} ({})",
+            self.archive_path.to_string_lossy(),
+            self.entry_name
+        )
+    }
+}
+
+/// Opens the zip archive for reading its entries
+fn open_archive(archive_path: &path::Path) -> Result<zip::ZipArchive<fs::File>, SubtitleFileError> {
+    fs::File::open(archive_path)
+        .map_err(zip::result::ZipError::from)
+        .and_then(zip::ZipArchive::new)
+        .map_err(|err| SubtitleFileError::FileSystem(err.to_string()))
+}
+
+/// Returns whether the entry of an archive is a subtitle file, directories being left out
+fn is_subtitle_entry(entry_name: &str) -> bool {
+    !entry_name.ends_with('/')
+        && path::Path::new(entry_name)
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case(crate::SUBTITLE_FILE_EXTENSION))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ZippedSubtitleFile;
+    use crate::test_directory::test_directory;
+    use crate::{MovieFile, RenameOptions, RenameOutcome};
+    use std::fs;
+    use std::io::{Read, Write};
+
+    #[test]
+    fn rename_zipped_subtitle_round_trip_test() {
+        let directory = test_directory("zipped");
+        let movie_path = directory.join("Show.S01E02.mkv");
+        let archive_path = directory.join("show s01e02 web.zip");
+        fs::write(&movie_path, "").unwrap();
+
+        let mut writer = zip::ZipWriter::new(fs::File::create(&archive_path).unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+        writer.start_file("show s01e02 web.srt", options).unwrap();
+        writer.write_all(b"subtitle").unwrap();
+        writer.start_file("notes.txt", options).unwrap();
+        writer.write_all(b"release notes").unwrap();
+        writer.finish().unwrap();
+
+        let movie_file = MovieFile::new(movie_path, None).unwrap();
+        let zipped_subtitle_file = ZippedSubtitleFile::open(archive_path.clone()).unwrap();
+        assert_eq!(zipped_subtitle_file.entry_name(), "show s01e02 web.srt");
+
+        let new_archive_path = directory.join("Show.S01E02.zip");
+        assert_eq!(
+            zipped_subtitle_file
+                .rename_using_movie_file_with_options(&movie_file, &RenameOptions::default())
+                .unwrap(),
+            RenameOutcome::Moved(new_archive_path.clone())
+        );
+        assert!(!archive_path.exists());
+
+        let mut archive = zip::ZipArchive::new(fs::File::open(&new_archive_path).unwrap()).unwrap();
+        let mut subtitle = String::new();
+        archive
+            .by_name("Show.S01E02.srt")
+            .unwrap()
+            .read_to_string(&mut subtitle)
+            .unwrap();
+        assert_eq!(subtitle, "subtitle");
+        assert!(archive.by_name("notes.txt").is_ok());
+
+        // Renaming once again has nothing left to do
+        assert_eq!(
+            ZippedSubtitleFile::open(new_archive_path)
+                .unwrap()
+                .rename_using_movie_file_with_options(&movie_file, &RenameOptions::default())
+                .unwrap(),
+            RenameOutcome::AlreadyCorrect
+        );
+    }
+}